@@ -1,92 +1,390 @@
-use std::collections::HashSet;
-
-fn flash(octopuses: &mut Vec<Vec<u32>>, (x, y): (usize, usize)) {
-    let adjacent = vec![
-        (x as i32 - 1, y as i32 - 1),
-        (x as i32, y as i32 - 1),
-        (x as i32 + 1, y as i32 - 1),
-        (x as i32 - 1, y as i32),
-        (x as i32 + 1, y as i32),
-        (x as i32 - 1, y as i32 + 1),
-        (x as i32, y as i32 + 1),
-        (x as i32 + 1, y as i32 + 1),
-    ]
-    .into_iter()
-    .filter(|&(x, y)| !(x < 0 || y < 0 || x > 9 || y > 9));
-
-    for (x, y) in adjacent {
-        octopuses[y as usize][x as usize] += 1;
+use std::{collections::HashSet, str::FromStr};
+
+use utils::{
+    random::XorShift,
+    simulation::{Driver, Scratch, Simulation},
+};
+
+#[derive(Debug)]
+enum Error {
+    Io(std::io::Error),
+    Empty,
+    InvalidLevel {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    RaggedRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    InvalidOption(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {}", e),
+            Self::Empty => f.write_str("empty grid"),
+            Self::InvalidLevel {
+                line,
+                column,
+                found,
+            } => write!(
+                f,
+                "line {}, column {}: invalid energy level {:?}",
+                line, column, found
+            ),
+            Self::RaggedRow {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {} octopuses, found {}",
+                line, expected, found
+            ),
+            Self::InvalidOption(s) => write!(f, "invalid option: {}", s),
+        }
     }
 }
 
-fn next_step(octopuses: &mut Vec<Vec<u32>>) -> u32 {
-    for row in octopuses.iter_mut() {
-        for oct in row.iter_mut() {
-            *oct += 1;
+impl std::error::Error for Error {}
+
+/// Energy levels of a rectangular grid of octopuses, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Octopuses {
+    width: usize,
+    height: usize,
+    levels: Vec<u8>,
+    queue: Scratch<Vec<usize>>,
+}
+
+impl Octopuses {
+    fn new(width: usize, height: usize, levels: Vec<u8>) -> Self {
+        Self {
+            width,
+            height,
+            levels,
+            queue: Scratch::default(),
         }
     }
 
-    let mut flashed: HashSet<(usize, usize)> = HashSet::new();
-    let mut flashes = 0;
+    fn cells(&self) -> usize {
+        self.width * self.height
+    }
+
+    /// Indices of the octopuses around the one at `i`.
+    fn adjacent(&self, i: usize) -> impl Iterator<Item = usize> {
+        let (x, y) = (i % self.width, i / self.width);
+        let (width, height) = (self.width, self.height);
 
-    loop {
-        let mut to_flash = HashSet::new();
+        (y.saturating_sub(1)..(y + 2).min(height)).flat_map(move |ay| {
+            (x.saturating_sub(1)..(x + 2).min(width))
+                .filter(move |&ax| (ax, ay) != (x, y))
+                .map(move |ax| ay * width + ax)
+        })
+    }
+
+    /// Runs one step and returns how many octopuses flashed.
+    ///
+    /// An octopus is queued the moment its level goes past 9, so each one is
+    /// looked at once however long the cascade gets.
+    fn next_step(&mut self) -> u32 {
+        let mut queue = std::mem::take(&mut self.queue);
+
+        for (i, oct) in self.levels.iter_mut().enumerate() {
+            *oct += 1;
+            if *oct == 10 {
+                queue.push(i);
+            }
+        }
 
-        for (y, row) in octopuses.iter().enumerate() {
-            for (x, oct) in row.iter().enumerate() {
-                if !flashed.contains(&(x, y)) && *oct > 9 {
-                    to_flash.insert((x, y));
+        let mut flashes = 0;
+        while let Some(i) = queue.pop() {
+            flashes += 1;
+            for a in self.adjacent(i) {
+                self.levels[a] += 1;
+                if self.levels[a] == 10 {
+                    queue.push(a);
                 }
             }
         }
+        self.queue = queue;
+
+        for oct in self.levels.iter_mut() {
+            if *oct > 9 {
+                *oct = 0;
+            }
+        }
 
-        if to_flash.len() == 0 {
-            break;
+        flashes
+    }
+
+    /// The same step done by rescanning the grid for new flashes until there
+    /// are none, which is what `next_step` replaced. Kept to compare against.
+    fn rescan_step(&mut self) -> u32 {
+        for oct in self.levels.iter_mut() {
+            *oct += 1;
         }
 
-        flashes += to_flash.len() as u32;
+        let mut flashed: HashSet<usize> = HashSet::new();
+        let mut flashes = 0;
+
+        loop {
+            let to_flash: HashSet<usize> = (0..self.levels.len())
+                .filter(|i| !flashed.contains(i) && self.levels[*i] > 9)
+                .collect();
+
+            if to_flash.is_empty() {
+                break;
+            }
 
-        for coord in to_flash.into_iter() {
-            flash(octopuses, coord);
-            flashed.insert(coord);
+            flashes += to_flash.len() as u32;
+
+            for i in to_flash.into_iter() {
+                for a in self.adjacent(i).collect::<Vec<_>>() {
+                    self.levels[a] += 1;
+                }
+                flashed.insert(i);
+            }
         }
-    }
 
-    for row in octopuses.iter_mut() {
-        for oct in row.iter_mut() {
+        for oct in self.levels.iter_mut() {
             if *oct > 9 {
                 *oct = 0;
             }
         }
+
+        flashes
+    }
+}
+
+impl Simulation for Octopuses {
+    type Output = u32;
+
+    fn step(&mut self) -> u32 {
+        self.next_step()
+    }
+}
+
+impl FromStr for Octopuses {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut levels = vec![];
+        let mut width = None;
+
+        for (i, line) in s.lines().enumerate() {
+            let row: Vec<u8> = line
+                .chars()
+                .enumerate()
+                .map(|(column, c)| {
+                    c.to_digit(10).map(|d| d as u8).ok_or(Error::InvalidLevel {
+                        line: i + 1,
+                        column: column + 1,
+                        found: c,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let expected = *width.get_or_insert(row.len());
+            if row.is_empty() || row.len() != expected {
+                return Err(Error::RaggedRow {
+                    line: i + 1,
+                    expected,
+                    found: row.len(),
+                });
+            }
+            levels.extend(row);
+        }
+
+        match width {
+            Some(width) => Ok(Self::new(width, levels.len() / width, levels)),
+            None => Err(Error::Empty),
+        }
     }
+}
+
+/// Number of flashes in each of the first `steps` steps.
+fn flash_counts(octopuses: &Octopuses, steps: usize) -> Vec<u32> {
+    let mut octopuses = octopuses.clone();
+    (0..steps).map(|_| octopuses.next_step()).collect()
+}
+
+/// The first step in which every octopus flashes, or `None` if the grid
+/// starts repeating before that happens.
+fn first_synchronised(driver: &mut Driver<Octopuses>) -> Option<usize> {
+    let cells = driver.current().cells() as u32;
+
+    while let Some(flashes) = driver.step() {
+        if flashes == cells {
+            return Some(driver.steps());
+        }
+    }
+
+    None
+}
+
+fn generate_octopuses(width: usize, height: usize, seed: u64) -> Octopuses {
+    let mut rng = XorShift::new(seed);
+    let levels = (0..width * height).map(|_| rng.below(10) as u8).collect();
+    Octopuses::new(width, height, levels)
+}
+
+/// Runs both ways of stepping on a generated grid, checking they agree.
+fn bench(size: usize, steps: usize) {
+    let mut rescanned = generate_octopuses(size, size, 11);
+    let mut queued = rescanned.clone();
+
+    let start = std::time::Instant::now();
+    let flashes: Vec<u32> = (0..steps).map(|_| rescanned.rescan_step()).collect();
+    let rescan_time = start.elapsed();
+
+    let start = std::time::Instant::now();
+    let queued_flashes: Vec<u32> = (0..steps).map(|_| queued.next_step()).collect();
+    let queue_time = start.elapsed();
 
-    flashes
+    assert_eq!(flashes, queued_flashes);
+    assert!(rescanned == queued);
+    println!(
+        "{}x{}, {} steps: rescan {:?}, queue {:?} ({:.1}x)",
+        size,
+        size,
+        steps,
+        rescan_time,
+        queue_time,
+        rescan_time.as_secs_f64() / queue_time.as_secs_f64()
+    );
 }
 
-fn main() {
-    let mut octopuses: Vec<Vec<u32>> = INPUT
-        .split('\n')
-        .map(|row| row.chars().map(|c| c.to_digit(10).unwrap()).collect())
-        .collect();
-
-    let mut counter = 0;
-    loop {
-        counter += 1;
-        if next_step(&mut octopuses) == 100 {
-            break;
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut input_file = "day11/input.txt".to_owned();
+    let mut steps = 100;
+    let mut verbose = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--steps" => {
+                steps = args
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .ok_or_else(|| Error::InvalidOption("--steps needs a number".to_owned()))?
+            }
+            "--verbose" => verbose = true,
+            "--bench" => {
+                for size in [10, 100, 250] {
+                    bench(size, 100);
+                }
+                return Ok(());
+            }
+            _ => input_file = arg,
+        }
+    }
+
+    let octopuses: Octopuses = std::fs::read_to_string(input_file)
+        .map_err(Error::Io)?
+        .parse()?;
+
+    let counts = flash_counts(&octopuses, steps);
+    if verbose {
+        for (i, flashes) in counts.iter().enumerate() {
+            println!("Step {}: {} flashes", i + 1, flashes);
         }
     }
+    println!(
+        "Part1: {} flashes after {} steps",
+        counts.iter().sum::<u32>(),
+        steps
+    );
+
+    let mut driver = Driver::new(octopuses);
+    match first_synchronised(&mut driver) {
+        Some(step) => println!("Part2: all flash in step {}", step),
+        None => println!("Part2: they never all flash at once"),
+    }
+
+    // Once they all flash together they keep doing so, so the grid repeats.
+    if let Some(cycle) = driver.find_cycle(driver.steps() + 1000) {
+        println!(
+            "Repeats every {} steps from step {}",
+            cycle.period, cycle.offset
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "5483143223
+2745854711
+5264556173
+6141336146
+6357385478
+4167524645
+2176841721
+6882881134
+4846848554
+5283751526";
+
+#[test]
+fn test_example() {
+    let octopuses: Octopuses = EXAMPLE.parse().unwrap();
 
-    println!("After {} steps", counter);
+    let counts = flash_counts(&octopuses, 100);
+    assert_eq!(counts[..10].iter().sum::<u32>(), 204);
+    assert_eq!(counts.iter().sum::<u32>(), 1656);
+
+    assert_eq!(first_synchronised(&mut Driver::new(octopuses)), Some(195));
+}
+
+#[test]
+fn test_other_sizes() {
+    // Every octopus of a 3x2 grid is next to every other one.
+    let octopuses: Octopuses = "999\n999".parse().unwrap();
+    assert_eq!(octopuses.cells(), 6);
+    assert_eq!(flash_counts(&octopuses, 2), vec![6, 0]);
+
+    // The one in the middle of a row is the only one two flashes reach.
+    let octopuses: Octopuses = "979".parse().unwrap();
+    assert_eq!(flash_counts(&octopuses, 1), vec![3]);
+
+    let octopuses: Octopuses = "1".parse().unwrap();
+    assert_eq!(first_synchronised(&mut Driver::new(octopuses)), Some(9));
 }
 
-const INPUT: &'static str = r#"4764745784
-4643457176
-8322628477
-7617152546
-6137518165
-1556723176
-2187861886
-2553422625
-4817584638
-3754285662"#;
+#[test]
+fn test_queue_matches_rescan() {
+    for (width, height, seed) in [(10, 10, 1), (37, 5, 2), (1, 50, 3), (30, 20, 4)] {
+        let mut rescanned = generate_octopuses(width, height, seed);
+        let mut queued = rescanned.clone();
+
+        for _ in 0..100 {
+            assert_eq!(rescanned.rescan_step(), queued.next_step());
+        }
+        assert_eq!(rescanned, queued);
+    }
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!(matches!(
+        "12\n1a".parse::<Octopuses>(),
+        Err(Error::InvalidLevel {
+            line: 2,
+            column: 2,
+            found: 'a'
+        })
+    ));
+    assert!(matches!(
+        "12\n123".parse::<Octopuses>(),
+        Err(Error::RaggedRow {
+            line: 2,
+            expected: 2,
+            found: 3
+        })
+    ));
+    assert!(matches!("".parse::<Octopuses>(), Err(Error::Empty)));
+}
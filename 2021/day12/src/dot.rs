@@ -0,0 +1,119 @@
+use std::{collections::HashMap, fmt::Write};
+
+use crate::graph::{CaveId, Graph, Size};
+
+/// What to draw on top of the caves and their connections.
+pub enum Overlay {
+    None,
+    /// A single path, labelling each edge with the steps that take it.
+    Path(Vec<CaveId>),
+    /// How many paths take each edge, keyed with the lower id first.
+    Usage(HashMap<(CaveId, CaveId), u64>),
+}
+
+fn edge_key(a: CaveId, b: CaveId) -> (CaveId, CaveId) {
+    (a.min(b), a.max(b))
+}
+
+/// Counts the edges `path` takes into `usage`.
+pub fn add_usage(usage: &mut HashMap<(CaveId, CaveId), u64>, path: &[CaveId]) {
+    for w in path.windows(2) {
+        *usage.entry(edge_key(w[0], w[1])).or_default() += 1;
+    }
+}
+
+fn node_style(graph: &Graph, cave: CaveId) -> &'static str {
+    if graph.start().ok() == Some(cave) {
+        "shape=doublecircle, style=filled, fillcolor=palegreen"
+    } else if graph.end().ok() == Some(cave) {
+        "shape=doublecircle, style=filled, fillcolor=salmon"
+    } else {
+        match graph.size(cave) {
+            Size::Small => "shape=circle",
+            Size::Large => "shape=box, style=\"filled,bold\", fillcolor=lightgrey",
+        }
+    }
+}
+
+/// Writes the cave system as an undirected Graphviz graph.
+pub fn to_dot(graph: &Graph, overlay: &Overlay) -> String {
+    let mut steps: HashMap<(CaveId, CaveId), Vec<usize>> = HashMap::new();
+    if let Overlay::Path(path) = overlay {
+        for (i, w) in path.windows(2).enumerate() {
+            steps.entry(edge_key(w[0], w[1])).or_default().push(i + 1);
+        }
+    }
+    let most_used = match overlay {
+        Overlay::Usage(usage) => usage.values().copied().max().unwrap_or(0),
+        _ => 0,
+    };
+
+    let mut dot = String::from("graph caves {\n");
+
+    for cave in graph.caves() {
+        writeln!(
+            dot,
+            "    \"{}\" [{}];",
+            graph.name(cave),
+            node_style(graph, cave)
+        )
+        .unwrap();
+    }
+
+    for (a, b) in graph.edges() {
+        let style = match overlay {
+            Overlay::None => String::new(),
+            Overlay::Path(_) => match steps.get(&(a, b)) {
+                Some(steps) => {
+                    let steps: Vec<String> = steps.iter().map(|s| s.to_string()).collect();
+                    format!(" [color=red, penwidth=2.5, label=\"{}\"]", steps.join(","))
+                }
+                None => " [color=grey]".to_owned(),
+            },
+            Overlay::Usage(usage) => {
+                let used = usage.get(&(a, b)).copied().unwrap_or(0);
+                let width = 1.0 + 5.0 * used as f64 / most_used.max(1) as f64;
+                format!(" [penwidth={:.2}, label=\"{}\"]", width, used)
+            }
+        };
+
+        writeln!(
+            dot,
+            "    \"{}\" -- \"{}\"{};",
+            graph.name(a),
+            graph.name(b),
+            style
+        )
+        .unwrap();
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+#[test]
+fn test_dot() {
+    let graph: Graph = "start-A\nA-b\nb-end\nA-end".parse().unwrap();
+
+    let plain = to_dot(&graph, &Overlay::None);
+    assert!(plain.starts_with("graph caves {\n"));
+    assert!(
+        plain.contains("    \"start\" [shape=doublecircle, style=filled, fillcolor=palegreen];\n")
+    );
+    assert!(plain.contains("    \"b\" [shape=circle];\n"));
+    assert!(plain.contains("    \"A\" -- \"end\";\n"));
+
+    let path = graph.parse_path("start,A,b,A,end").unwrap();
+    let dot = to_dot(&graph, &Overlay::Path(path.clone()));
+    assert!(dot.contains("    \"A\" -- \"b\" [color=red, penwidth=2.5, label=\"2,3\"];\n"));
+    assert!(dot.contains("    \"b\" -- \"end\" [color=grey];\n"));
+
+    let other = graph.parse_path("start,A,end").unwrap();
+    let mut usage = HashMap::new();
+    add_usage(&mut usage, &path);
+    add_usage(&mut usage, &other);
+    let dot = to_dot(&graph, &Overlay::Usage(usage));
+    assert!(dot.contains("    \"start\" -- \"A\" [penwidth=6.00, label=\"2\"];\n"));
+    assert!(dot.contains("    \"A\" -- \"b\" [penwidth=6.00, label=\"2\"];\n"));
+    assert!(dot.contains("    \"b\" -- \"end\" [penwidth=1.00, label=\"0\"];\n"));
+}
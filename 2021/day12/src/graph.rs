@@ -0,0 +1,217 @@
+use std::{collections::HashMap, str::FromStr};
+
+use crate::Error;
+
+/// Index of a cave in its `Graph`. Every name gets its own id, in the order
+/// the names first come up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CaveId(usize);
+
+impl CaveId {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Size {
+    Small,
+    Large,
+}
+
+impl Size {
+    /// Lowercase names are small caves and uppercase ones large.
+    fn of(name: &str) -> Option<Size> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic()) {
+            None
+        } else if name.chars().all(|c| c.is_ascii_lowercase()) {
+            Some(Size::Small)
+        } else if name.chars().all(|c| c.is_ascii_uppercase()) {
+            Some(Size::Large)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    names: Vec<String>,
+    ids: HashMap<String, CaveId>,
+    sizes: Vec<Size>,
+    neighbours: Vec<Vec<CaveId>>,
+}
+
+impl Graph {
+    /// The id of `name`, adding the cave if it is new.
+    fn intern(&mut self, name: &str, size: Size) -> CaveId {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+
+        let id = CaveId(self.names.len());
+        self.names.push(name.to_owned());
+        self.ids.insert(name.to_owned(), id);
+        self.sizes.push(size);
+        self.neighbours.push(vec![]);
+        id
+    }
+
+    /// Connects two caves both ways. Connecting them again changes nothing.
+    fn connect(&mut self, a: CaveId, b: CaveId) {
+        if !self.neighbours[a.0].contains(&b) {
+            self.neighbours[a.0].push(b);
+            self.neighbours[b.0].push(a);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn caves(&self) -> impl Iterator<Item = CaveId> {
+        (0..self.len()).map(CaveId)
+    }
+
+    pub fn id(&self, name: &str) -> Option<CaveId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: CaveId) -> &str {
+        &self.names[id.0]
+    }
+
+    pub fn size(&self, id: CaveId) -> Size {
+        self.sizes[id.0]
+    }
+
+    pub fn neighbours(&self, id: CaveId) -> &[CaveId] {
+        &self.neighbours[id.0]
+    }
+
+    /// Every connection once, with the lower id first.
+    pub fn edges(&self) -> impl Iterator<Item = (CaveId, CaveId)> + '_ {
+        self.caves().flat_map(move |a| {
+            self.neighbours(a)
+                .iter()
+                .filter(move |b| a < **b)
+                .map(move |b| (a, *b))
+        })
+    }
+
+    pub fn start(&self) -> Result<CaveId, Error> {
+        self.id("start").ok_or(Error::MissingCave("start"))
+    }
+
+    pub fn end(&self) -> Result<CaveId, Error> {
+        self.id("end").ok_or(Error::MissingCave("end"))
+    }
+
+    /// Writes a path the way the puzzle does, e.g. `start,A,b,end`.
+    pub fn format_path(&self, path: &[CaveId]) -> String {
+        path.iter()
+            .map(|id| self.name(*id))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Reads a path written like `format_path` does, checking that the caves
+    /// exist and each one is connected to the next.
+    pub fn parse_path(&self, s: &str) -> Result<Vec<CaveId>, Error> {
+        let invalid = || Error::InvalidPath(s.to_owned());
+        let path = s
+            .trim()
+            .split(',')
+            .map(|name| self.id(name).ok_or_else(invalid))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match path
+            .windows(2)
+            .all(|w| self.neighbours(w[0]).contains(&w[1]))
+        {
+            true => Ok(path),
+            false => Err(invalid()),
+        }
+    }
+}
+
+/// Parses one `a-b` edge per line, skipping blank lines.
+impl FromStr for Graph {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut graph = Graph::default();
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let invalid = || Error::InvalidEdge {
+                line: i + 1,
+                text: line.to_owned(),
+            };
+            let (a, b) = line.split_once('-').ok_or_else(invalid)?;
+            if a == b {
+                return Err(invalid());
+            }
+
+            let a = graph.intern(a, Size::of(a).ok_or_else(invalid)?);
+            let b = graph.intern(b, Size::of(b).ok_or_else(invalid)?);
+            graph.connect(a, b);
+        }
+
+        Ok(graph)
+    }
+}
+
+#[test]
+fn test_interning() {
+    // Summing `(i + 1) * c` over the names gives 295 for both of these.
+    let graph: Graph = "start-ac\nac-cb\ncb-end\nac-cb\n".parse().unwrap();
+
+    assert_eq!(graph.len(), 4);
+    let (ac, cb) = (graph.id("ac").unwrap(), graph.id("cb").unwrap());
+    assert_ne!(ac, cb);
+    assert_eq!(graph.neighbours(ac), &[graph.start().unwrap(), cb]);
+    assert_eq!(graph.name(cb), "cb");
+    assert_eq!(
+        graph.format_path(&[graph.start().unwrap(), ac, cb, graph.end().unwrap()]),
+        "start,ac,cb,end"
+    );
+}
+
+#[test]
+fn test_paths_and_edges() {
+    let graph: Graph = "start-A\nA-b\nb-end\nA-end".parse().unwrap();
+
+    let named = |(a, b)| (graph.name(a), graph.name(b));
+    let edges: Vec<_> = graph.edges().map(named).collect();
+    assert_eq!(
+        edges,
+        vec![("start", "A"), ("A", "b"), ("A", "end"), ("b", "end")]
+    );
+
+    let path = graph.parse_path("start,A,b,A,end").unwrap();
+    assert_eq!(graph.format_path(&path), "start,A,b,A,end");
+    assert!(matches!(
+        graph.parse_path("start,b,end"),
+        Err(Error::InvalidPath(_))
+    ));
+    assert!(graph.parse_path("start,A,c").is_err());
+}
+
+#[test]
+fn test_parse_errors() {
+    for (input, line) in [("start-A\nA", 2), ("a-a", 1), ("start-Ab", 1), ("-b", 1)] {
+        match input.parse::<Graph>() {
+            Err(Error::InvalidEdge { line: l, .. }) => assert_eq!(l, line, "{:?}", input),
+            other => panic!("{:?} parsed as {:?}", input, other),
+        }
+    }
+    assert!(matches!(
+        "a-b".parse::<Graph>().unwrap().start(),
+        Err(Error::MissingCave("start"))
+    ));
+}
@@ -1,172 +1,134 @@
-use std::collections::{HashMap, HashSet};
+mod dot;
+mod graph;
+mod paths;
 
-#[derive(Debug, PartialEq, Clone, Copy, Eq, Hash)]
-enum Cave {
-    Start,
-    End,
-    Small(u32),
-    Large(u32),
+use dot::Overlay;
+use graph::Graph;
+use paths::{Paths, Policy};
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    InvalidEdge {
+        line: usize,
+        text: String,
+    },
+    MissingCave(&'static str),
+    InvalidPath(String),
+    /// Two large caves are connected, so there are endless paths.
+    Unbounded(String, String),
+    TooManySmallCaves(usize),
+    InvalidOption(String),
 }
 
-impl Cave {
-    fn from_name(name: &str) -> Cave {
-        if name == "start" {
-            Cave::Start
-        } else if name == "end" {
-            Cave::End
-        } else if name.to_lowercase() == *name {
-            let id = name
-                .chars()
-                .enumerate()
-                .map(|(i, c)| ((i + 1) * (c as usize)) as u32)
-                .sum();
-            Cave::Small(id)
-        } else {
-            let id = name
-                .chars()
-                .enumerate()
-                .map(|(i, c)| ((i + 1) * (c as usize)) as u32)
-                .sum();
-            Cave::Large(id)
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {}", e),
+            Self::InvalidEdge { line, text } => write!(f, "line {}: invalid edge {:?}", line, text),
+            Self::MissingCave(name) => write!(f, "there is no {} cave", name),
+            Self::InvalidPath(s) => write!(f, "invalid path {:?}", s),
+            Self::Unbounded(a, b) => write!(
+                f,
+                "large caves {} and {} are connected, so there are endless paths",
+                a, b
+            ),
+            Self::TooManySmallCaves(n) => write!(f, "{} small caves, at most 64 are supported", n),
+            Self::InvalidOption(s) => write!(f, "invalid option: {}", s),
         }
     }
 }
 
-#[derive(Debug)]
-struct Edge {
-    name: Cave,
-    neighbors: Vec<Cave>,
-}
+impl std::error::Error for Error {}
 
-fn can_go_into(edge: &Cave, visited: &HashMap<Cave, u32>) -> bool {
-    if let Cave::Start = edge {
-        // cannot to into start
-        return false;
-    }
+fn read_graph(path: &str) -> Result<Graph, Error> {
+    utils::read_input(path).map_err(Error::Io)?.parse()
+}
 
-    if let Cave::Large(_) = edge {
-        // can go into large cave
-        return true;
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut input_file = "day12/input.txt".to_owned();
+    let mut print_paths = false;
+    let mut extra = None;
+    let mut dot = None;
+    let mut dot_path = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--paths" => print_paths = true,
+            "--extra" => {
+                let k = args.next().and_then(|k| k.parse().ok());
+                extra =
+                    Some(k.ok_or_else(|| {
+                        Error::InvalidOption("--extra needs a number".to_owned())
+                    })?);
+            }
+            "--dot" => dot = Some(Overlay::None),
+            "--dot-usage" => dot = Some(Overlay::Usage(Default::default())),
+            "--dot-path" => {
+                let path = args.next();
+                dot_path = Some(path.ok_or_else(|| {
+                    Error::InvalidOption("--dot-path needs a path like start,A,end".to_owned())
+                })?);
+            }
+            _ => input_file = arg,
+        }
     }
 
-    let small_visited_twice = *visited
-        .iter()
-        .filter(|(x, _)| if let Cave::Small(_) = x { true } else { false })
-        .map(|(_, v)| v)
-        .max()
-        .unwrap()
-        == 2;
+    let graph = read_graph(&input_file)?;
+    let policy = match extra {
+        Some(k) => Policy::Extra(k),
+        None => Policy::OneTwice,
+    };
 
-    if small_visited_twice {
-        if *visited.get(&edge).unwrap() == 0 {
-            return true;
-        }
-        return false;
-    } else {
-        return true;
+    if let Some(path) = dot_path {
+        dot = Some(Overlay::Path(graph.parse_path(&path)?));
     }
-}
-
-fn get_paths(
-    edge: &Edge,
-    graph: &HashMap<Cave, Edge>,
-    path: Vec<Cave>,
-    visited: HashMap<Cave, u32>,
-    paths: &mut Vec<Vec<Cave>>,
-) {
-    if edge.name == Cave::End {
-        paths.push(path);
-        return;
+    if let Some(mut overlay) = dot {
+        if let Overlay::Usage(usage) = &mut overlay {
+            Paths::new(&graph, policy)?.for_each(|path| dot::add_usage(usage, path));
+        }
+        print!("{}", dot::to_dot(&graph, &overlay));
+        return Ok(());
     }
 
-    let mut new_visited = visited.clone();
-    *new_visited.get_mut(&edge.name).unwrap() += 1;
-
-    for neighbor in edge.neighbors.iter() {
-        let next_edge = graph.get(neighbor).unwrap();
-
-        if !can_go_into(&next_edge.name, &new_visited) {
-            continue;
+    if let Some(k) = extra {
+        let paths = Paths::new(&graph, policy)?;
+        if print_paths {
+            paths.for_each(|path| println!("{}", graph.format_path(path)));
         }
-
-        let mut next_path = path.clone();
-        next_path.push(next_edge.name.clone());
-
-        get_paths(next_edge, graph, next_path, new_visited.clone(), paths);
+        println!("Paths with {} extra visits: {}", k, paths.count());
+        return Ok(());
     }
-}
-
-fn main() {
-    let graph = get_input();
-    let edge = graph.get(&Cave::Start).unwrap();
-
-    let mut paths = Vec::new();
-    let visited = HashMap::from_iter(graph.iter().map(|(v, _)| (v.to_owned(), 0)));
-
-    get_paths(edge, &graph, vec![Cave::Start], visited, &mut paths);
-
-    println!("{:?}", paths.len());
-}
 
-fn get_input() -> HashMap<Cave, Edge> {
-    let edges: Vec<(String, String)> = vec![
-        ("we", "NX"),
-        ("ys", "px"),
-        ("ys", "we"),
-        ("px", "end"),
-        ("yq", "NX"),
-        ("px", "NX"),
-        ("yq", "px"),
-        ("qk", "yq"),
-        ("pr", "NX"),
-        ("wq", "EY"),
-        ("pr", "oe"),
-        ("wq", "pr"),
-        ("ys", "end"),
-        ("start", "we"),
-        ("ys", "start"),
-        ("oe", "DW"),
-        ("EY", "oe"),
-        ("end", "oe"),
-        ("pr", "yq"),
-        ("pr", "we"),
-        ("wq", "start"),
-        ("oe", "NX"),
-        ("yq", "EY"),
-        ("ys", "wq"),
-        ("ys", "pr"),
-    ]
-    .into_iter()
-    .map(|(s, e)| (s.to_owned(), e.to_owned()))
-    .collect();
-
-    let mut vertices = HashSet::new();
-    for (v1, v2) in edges.iter() {
-        vertices.insert(v1.clone());
-        vertices.insert(v2.clone());
+    let part1 = Paths::new(&graph, Policy::EachOnce)?;
+    let part2 = Paths::new(&graph, policy)?;
+    if print_paths {
+        part2.for_each(|path| println!("{}", graph.format_path(path)));
     }
+    println!("Part1: {}", part1.count());
+    println!("Part2: {}", part2.count());
 
-    let mut graph: HashMap<Cave, Edge> = HashMap::new();
-
-    for v in vertices.iter() {
-        let mut neighbor: Vec<Cave> = vec![];
-
-        for (v1, v2) in edges.iter() {
-            if *v == *v1 {
-                neighbor.push(Cave::from_name(v2));
-            } else if *v == *v2 {
-                neighbor.push(Cave::from_name(v1));
-            }
-        }
+    Ok(())
+}
 
-        graph.insert(
-            Cave::from_name(v),
-            Edge {
-                name: Cave::from_name(v),
-                neighbors: neighbor,
-            },
-        );
-    }
+#[cfg(test)]
+const EXAMPLES: [&str; 3] = [
+    "start-A\nstart-b\nA-c\nA-b\nb-d\nA-end\nb-end",
+    "dc-end\nHN-start\nstart-kj\ndc-start\ndc-HN\nLN-dc\nHN-end\nkj-sa\nkj-HN\nkj-dc",
+    "fs-end\nhe-DX\nfs-he\nstart-DX\npj-DX\nend-zg\nzg-sl\nzg-pj\npj-he\nRW-he\nfs-DX\npj-RW\n\
+     zg-RW\nstart-pj\nhe-WI\nzg-he\npj-fs\nstart-RW",
+];
+
+#[test]
+fn test_path_names() {
+    let graph: Graph = EXAMPLES[0].parse().unwrap();
+    let mut paths = vec![];
+    Paths::new(&graph, Policy::OneTwice)
+        .unwrap()
+        .for_each(|path| paths.push(graph.format_path(path)));
 
-    graph
+    assert!(paths.contains(&"start,A,b,A,b,A,c,A,end".to_owned()));
+    assert!(paths.contains(&"start,b,d,b,A,end".to_owned()));
+    assert!(!paths.contains(&"start,b,d,b,A,b,end".to_owned()));
 }
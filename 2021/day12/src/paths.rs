@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use crate::{
+    graph::{CaveId, Graph, Size},
+    Error,
+};
+
+/// How often small caves may be entered. Start is never entered again and a
+/// path stops as soon as it reaches end, whatever the policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// Each small cave at most once (part 1).
+    EachOnce,
+    /// A single small cave twice and the others once (part 2).
+    OneTwice,
+    /// Up to this many visits to small caves that were already visited,
+    /// shared between all of them.
+    Extra(u32),
+}
+
+impl Policy {
+    pub fn extra_visits(&self) -> u32 {
+        match self {
+            Policy::EachOnce => 0,
+            Policy::OneTwice => 1,
+            Policy::Extra(k) => *k,
+        }
+    }
+}
+
+/// Paths from start to end, with the visited small caves kept as a bitmask.
+pub struct Paths<'a> {
+    graph: &'a Graph,
+    start: CaveId,
+    end: CaveId,
+    extra: u32,
+    /// Bit of each small cave in the visited mask.
+    bits: Vec<Option<u64>>,
+}
+
+impl<'a> Paths<'a> {
+    pub fn new(graph: &'a Graph, policy: Policy) -> Result<Self, Error> {
+        // Two connected large caves could be walked back and forth forever.
+        for cave in graph.caves().filter(|c| graph.size(*c) == Size::Large) {
+            let large = graph.neighbours(cave).iter();
+            if let Some(other) = large.copied().find(|n| graph.size(*n) == Size::Large) {
+                return Err(Error::Unbounded(
+                    graph.name(cave).to_owned(),
+                    graph.name(other).to_owned(),
+                ));
+            }
+        }
+
+        let small = graph.caves().filter(|c| graph.size(*c) == Size::Small);
+        let small = small.count();
+        if small > 64 {
+            return Err(Error::TooManySmallCaves(small));
+        }
+
+        let mut next_bit = 0;
+        let bits = graph
+            .caves()
+            .map(|c| match graph.size(c) {
+                Size::Small => {
+                    next_bit += 1;
+                    Some(1 << (next_bit - 1))
+                }
+                Size::Large => None,
+            })
+            .collect();
+
+        Ok(Self {
+            graph,
+            start: graph.start()?,
+            end: graph.end()?,
+            extra: policy.extra_visits(),
+            bits,
+        })
+    }
+
+    /// What entering `cave` does to the visited mask and the extra visits
+    /// used, or `None` if it may not be entered.
+    fn enter(&self, cave: CaveId, visited: u64, used: u32) -> Option<(u64, u32)> {
+        match self.bits[cave.index()] {
+            _ if cave == self.start => None,
+            None => Some((visited, used)),
+            Some(bit) if visited & bit == 0 => Some((visited | bit, used)),
+            Some(_) if used < self.extra => Some((visited, used + 1)),
+            Some(_) => None,
+        }
+    }
+
+    fn start_mask(&self) -> u64 {
+        self.bits[self.start.index()].unwrap_or(0)
+    }
+
+    /// Calls `f` with every path, reusing one buffer for all of them.
+    pub fn for_each(&self, mut f: impl FnMut(&[CaveId])) {
+        let mut path = vec![self.start];
+        self.walk(&mut path, self.start_mask(), 0, &mut f);
+    }
+
+    fn walk(&self, path: &mut Vec<CaveId>, visited: u64, used: u32, f: &mut impl FnMut(&[CaveId])) {
+        let cave = *path.last().unwrap();
+        if cave == self.end {
+            f(path);
+            return;
+        }
+
+        for neighbour in self.graph.neighbours(cave) {
+            if let Some((visited, used)) = self.enter(*neighbour, visited, used) {
+                path.push(*neighbour);
+                self.walk(path, visited, used, f);
+                path.pop();
+            }
+        }
+    }
+
+    /// Number of paths, without going through them one by one.
+    pub fn count(&self) -> u64 {
+        let mut memo = HashMap::new();
+        self.count_from(self.start, self.start_mask(), 0, &mut memo)
+    }
+
+    fn count_from(
+        &self,
+        cave: CaveId,
+        visited: u64,
+        used: u32,
+        memo: &mut HashMap<(CaveId, u64, u32), u64>,
+    ) -> u64 {
+        if cave == self.end {
+            return 1;
+        }
+        if let Some(count) = memo.get(&(cave, visited, used)) {
+            return *count;
+        }
+
+        let count = self
+            .graph
+            .neighbours(cave)
+            .iter()
+            .filter_map(|n| Some((*n, self.enter(*n, visited, used)?)))
+            .map(|(n, (visited, used))| self.count_from(n, visited, used, memo))
+            .sum();
+
+        memo.insert((cave, visited, used), count);
+        count
+    }
+}
+
+#[test]
+fn test_policies() {
+    let counts = |policy| -> Vec<u64> {
+        crate::EXAMPLES
+            .iter()
+            .map(|input| {
+                let graph = input.parse().unwrap();
+                Paths::new(&graph, policy).unwrap().count()
+            })
+            .collect()
+    };
+
+    assert_eq!(counts(Policy::EachOnce), vec![10, 19, 226]);
+    assert_eq!(counts(Policy::OneTwice), vec![36, 103, 3509]);
+    assert_eq!(counts(Policy::Extra(1)), counts(Policy::OneTwice));
+}
+
+#[test]
+fn test_count_matches_enumeration() {
+    for input in crate::EXAMPLES {
+        let graph = input.parse().unwrap();
+        for extra in 0..3 {
+            let paths = Paths::new(&graph, Policy::Extra(extra)).unwrap();
+            let mut walked = 0;
+            paths.for_each(|_| walked += 1);
+            assert_eq!(paths.count(), walked);
+        }
+    }
+}
+
+#[test]
+fn test_unbounded() {
+    let graph = "start-A\nA-B\nB-end".parse().unwrap();
+    assert!(matches!(
+        Paths::new(&graph, Policy::EachOnce),
+        Err(Error::Unbounded(..))
+    ));
+}
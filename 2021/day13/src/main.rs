@@ -1,13 +1,85 @@
 mod sheet;
 
-fn main() {
-    let mut inp = sheet::get_input();
+use sheet::{Fold, Sheet};
+use utils::ocr;
 
-    while let Some(f) = inp.fold() {
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    InvalidPoint {
+        line: usize,
+        text: String,
+    },
+    InvalidFold {
+        line: usize,
+        text: String,
+    },
+    DotOnFold {
+        fold: Fold,
+        point: (i32, i32),
+    },
+    /// The part folded over is larger than the part it goes onto, so dots would
+    /// end up past the edge, or the line is too far out to fold at. The sheet
+    /// is `size` long across the line.
+    FoldOutOfRange {
+        fold: Fold,
+        size: i32,
+    },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {}", e),
+            Self::InvalidPoint { line, text } => write!(f, "line {}: invalid dot {:?}", line, text),
+            Self::InvalidFold { line, text } => write!(f, "line {}: invalid fold {:?}", line, text),
+            Self::DotOnFold { fold, point } => {
+                write!(f, "{}: dot at {},{} is on the line", fold, point.0, point.1)
+            }
+            Self::FoldOutOfRange { fold, size } => {
+                write!(f, "{}: out of range for a sheet of size {}", fold, size)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+fn read_sheet(path: &str) -> Result<Sheet, Error> {
+    utils::read_input(path).map_err(Error::Io)?.parse()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let input_file = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "day13/input.txt".to_owned());
+    let mut inp = read_sheet(&input_file)?;
+
+    while let Some(f) = inp.fold()? {
         println!("Folding {:?}", f);
         println!("Points: {}", inp.points.len());
     }
 
     println!("Finished");
     println!("{}", inp);
+
+    match read_code(&inp) {
+        Ok(code) => println!("Code: {}", code),
+        Err(e) => println!("Could not read the code: {}", e),
+    }
+
+    Ok(())
+}
+
+/// The letters the dots make once everything is folded.
+fn read_code(sheet: &Sheet) -> Result<String, ocr::Error> {
+    ocr::read_points(sheet.points.iter().map(|&(x, y)| (x as usize, y as usize)))
+}
+
+#[test]
+fn test_read_code() {
+    let mut sheet = read_sheet("input.txt").unwrap();
+    while sheet.fold().unwrap().is_some() {}
+
+    assert_eq!(read_code(&sheet).unwrap(), "RGZLBHFP");
 }
@@ -0,0 +1,205 @@
+use std::rc::Rc;
+
+use utils::animation::{Frame, ToFrame};
+
+use crate::{Algorithm, Enhance};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Output pixels computed with one table lookup.
+const GROUP: usize = 4;
+
+/// Input columns a group of output pixels depends on.
+const GROUP_COLUMNS: usize = GROUP + 2;
+
+/// The algorithm applied to `GROUP` pixels at once. The index holds
+/// `GROUP_COLUMNS` columns of the top, middle and bottom rows, from the most
+/// significant bits down, with the leftmost column in the lowest bit of each
+/// row. Bit `i` of an entry is the output pixel centred on column `i + 1`.
+struct Lookup {
+    alg: Algorithm,
+    groups: Vec<u8>,
+}
+
+impl Lookup {
+    fn new(alg: &Algorithm) -> Self {
+        // The algorithm for a single pixel, indexed like the groups but with
+        // three columns per row.
+        let single: Vec<u8> = (0..512)
+            .map(|index: usize| {
+                let window = [6, 3, 0].iter().fold(0, |window, r| {
+                    let columns = index >> r;
+                    // The leftmost column is the most significant bit.
+                    window << 3 | (columns & 1) << 2 | (columns & 2) | (columns >> 2 & 1)
+                });
+                alg[window] as u8
+            })
+            .collect();
+
+        let groups = (0..1 << (3 * GROUP_COLUMNS))
+            .map(|index: usize| {
+                let rows = [2, 1, 0].map(|r| index >> (r * GROUP_COLUMNS));
+
+                (0..GROUP).fold(0, |out, i| {
+                    let window = rows
+                        .iter()
+                        .fold(0, |window, row| window << 3 | (row >> i) & 0b111);
+                    out | single[window] << i
+                })
+            })
+            .collect();
+
+        Self {
+            alg: alg.clone(),
+            groups,
+        }
+    }
+}
+
+/// Image stored as rows of packed `u64` words, one bit per pixel.
+pub struct BitImage {
+    fill_pixels: bool,
+    width: usize,
+    height: usize,
+    row_words: usize,
+    bits: Vec<u64>,
+    /// Built on the first step and shared with the images that follow.
+    lookup: Option<Rc<Lookup>>,
+}
+
+impl BitImage {
+    fn empty(width: usize, height: usize, fill_pixels: bool) -> Self {
+        let row_words = width.div_ceil(WORD_BITS);
+
+        Self {
+            fill_pixels,
+            width,
+            height,
+            row_words,
+            bits: vec![0; row_words * height],
+            lookup: None,
+        }
+    }
+
+    fn row(&self, y: isize) -> Option<&[u64]> {
+        if y < 0 || y as usize >= self.height {
+            return None;
+        }
+
+        let start = y as usize * self.row_words;
+        Some(&self.bits[start..start + self.row_words])
+    }
+
+    /// Returns the `i`-th word of a row, with pixels outside the image set to
+    /// the background.
+    fn word(&self, row: Option<&[u64]>, i: usize) -> u64 {
+        let fill_word = match self.fill_pixels {
+            false => 0,
+            true => !0,
+        };
+
+        match row {
+            Some(row) if i < self.row_words => {
+                let used_bits = self.width - i * WORD_BITS;
+                match used_bits < WORD_BITS {
+                    true => row[i] | (fill_word << used_bits),
+                    false => row[i],
+                }
+            }
+            _ => fill_word,
+        }
+    }
+
+    fn set(&mut self, x: usize, y: usize) {
+        self.bits[y * self.row_words + x / WORD_BITS] |= 1 << (x % WORD_BITS);
+    }
+}
+
+impl Enhance for BitImage {
+    fn from_pixels(pixels: &[Vec<bool>]) -> Self {
+        let width = pixels.first().map_or(0, |row| row.len());
+        let mut image = Self::empty(width, pixels.len(), false);
+
+        for (y, row) in pixels.iter().enumerate() {
+            for (x, _) in row.iter().enumerate().filter(|(_, p)| **p) {
+                image.set(x, y);
+            }
+        }
+
+        image
+    }
+
+    fn enhanced(&self, alg: &Algorithm) -> Self {
+        let fill_pixels = match self.fill_pixels {
+            false => alg[0],
+            true => alg[511],
+        };
+        let mut image = Self::empty(self.width + 2, self.height + 2, fill_pixels);
+        let lookup = match &self.lookup {
+            Some(lookup) if lookup.alg == *alg => lookup.clone(),
+            _ => Rc::new(Lookup::new(alg)),
+        };
+
+        // Output pixel (x, y) is centred on input pixel (x - 1, y - 1), so
+        // output bit `b` of word `i` needs input columns `64 * i + b - 2` up to
+        // `64 * i + b`.
+        let width = image.width;
+        let mask = (1 << GROUP_COLUMNS) - 1;
+        for (y, out_row) in image.bits.chunks_mut(image.row_words).enumerate() {
+            let rows = [
+                self.row(y as isize - 2),
+                self.row(y as isize - 1),
+                self.row(y as isize),
+            ];
+
+            for (i, out) in out_row.iter_mut().enumerate() {
+                // Bit `k` is input column `64 * i + k - 2`.
+                let columns = rows.map(|row| {
+                    let before = match i {
+                        // Left of the image there is only background.
+                        0 => self.word(None, 0),
+                        _ => self.word(row, i - 1),
+                    };
+                    (self.word(row, i) as u128) << 2 | (before >> (WORD_BITS - 2)) as u128
+                });
+
+                for bit in (0..WORD_BITS).step_by(GROUP) {
+                    let index = columns.iter().fold(0, |index, c| {
+                        index << GROUP_COLUMNS | ((c >> bit) as usize & mask)
+                    });
+                    *out |= (lookup.groups[index] as u64) << bit;
+                }
+
+                let used_bits = width - i * WORD_BITS;
+                if used_bits < WORD_BITS {
+                    *out &= (1 << used_bits) - 1;
+                }
+            }
+        }
+
+        image.lookup = Some(lookup);
+        image
+    }
+
+    fn lit_pixels(&self) -> usize {
+        self.bits.iter().map(|w| w.count_ones() as usize).sum()
+    }
+}
+
+impl ToFrame for BitImage {
+    /// Draws lit pixels with colour 1 and dark ones with colour 0. The
+    /// infinite background is used as the frame background.
+    fn to_frame(&self) -> Frame {
+        let mut frame = Frame::new(self.width, self.height, self.fill_pixels as u8);
+
+        for y in 0..self.height {
+            let row = self.row(y as isize);
+            for x in 0..self.width {
+                let word = self.word(row, x / WORD_BITS);
+                frame.set(x, y, ((word >> (x % WORD_BITS)) & 1) as u8);
+            }
+        }
+
+        frame
+    }
+}
@@ -1,35 +1,117 @@
-use std::{
-    fmt::Write,
-    fs::File,
-    io::{BufRead, BufReader},
-};
+mod bit_image;
+mod sparse_image;
+
+use std::{fmt::Write, time::Instant};
+
+use bit_image::BitImage;
+use sparse_image::SparseImage;
+use utils::animation::{self, Colour};
 
 type Algorithm = Vec<bool>;
 
+const ALGORITHM_LENGTH: usize = 512;
+
+#[derive(Debug)]
+enum Error {
+    Io(std::io::Error),
+    MissingAlgorithm,
+    AlgorithmLength(usize),
+    MissingSeparator(usize),
+    InvalidPixel {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    RowLength {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    EmptyImage,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {}", e),
+            Self::MissingAlgorithm => f.write_str("missing enhancement algorithm"),
+            Self::AlgorithmLength(len) => write!(
+                f,
+                "enhancement algorithm has {} characters, expected {}",
+                len, ALGORITHM_LENGTH
+            ),
+            Self::MissingSeparator(line) => {
+                write!(
+                    f,
+                    "line {}: expected an empty line after the algorithm",
+                    line
+                )
+            }
+            Self::InvalidPixel {
+                line,
+                column,
+                found,
+            } => write!(
+                f,
+                "line {}, column {}: invalid pixel {:?}",
+                line, column, found
+            ),
+            Self::RowLength {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: image row has {} pixels, expected {}",
+                line, found, expected
+            ),
+            Self::EmptyImage => f.write_str("input image is empty"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Dark and lit pixel colours used when recording an animation.
+const PALETTE: [Colour; 2] = [[0x0f, 0x0f, 0x23], [0xff, 0xff, 0x66]];
+
+trait Enhance: Sized {
+    fn from_pixels(pixels: &[Vec<bool>]) -> Self;
+    fn enhanced(&self, alg: &Algorithm) -> Self;
+
+    /// Counts lit pixels inside the image bounds, ignoring the infinite background.
+    fn lit_pixels(&self) -> usize;
+}
+
 struct Image {
     fill_pixels: bool,
     pixels: Vec<Vec<bool>>,
 }
 
-impl Image {
+impl Enhance for Image {
+    fn from_pixels(pixels: &[Vec<bool>]) -> Self {
+        Self {
+            fill_pixels: false,
+            pixels: pixels.to_vec(),
+        }
+    }
+
     fn enhanced(&self, alg: &Algorithm) -> Self {
-        let (size_x, size_y) = (self.pixels.len(), self.pixels[0].len());
+        let (size_x, size_y) = (self.pixels[0].len(), self.pixels.len());
         let (input_size_x, input_size_y) = (size_x + 4, size_y + 4);
         let (new_size_x, new_size_y) = (size_x + 2, size_y + 2);
 
-        let mut input_image = vec![vec![self.fill_pixels; input_size_y]; input_size_x];
-        let mut new_pixels = vec![vec![false; new_size_y]; new_size_x];
+        let mut input_image = vec![vec![self.fill_pixels; input_size_x]; input_size_y];
+        let mut new_pixels = vec![vec![false; new_size_x]; new_size_y];
 
-        for x in 0..size_x {
-            for y in 0..size_y {
-                input_image[y + 2][x + 2] = self.pixels[y][x];
-            }
+        for (y, row) in self.pixels.iter().enumerate() {
+            input_image[y + 2][2..size_x + 2].copy_from_slice(row);
         }
 
-        for x in 0..new_size_x {
-            for y in 0..new_size_y {
+        for (y, row) in new_pixels.iter_mut().enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
                 let index = Image::get_pixel_index(&input_image, x + 1, y + 1);
-                new_pixels[y][x] = alg[index];
+                *pixel = alg[index];
             }
         }
 
@@ -44,7 +126,16 @@ impl Image {
         }
     }
 
-    fn get_pixel_index(pixels: &Vec<Vec<bool>>, x: usize, y: usize) -> usize {
+    fn lit_pixels(&self) -> usize {
+        self.pixels
+            .iter()
+            .map(|row| row.iter().filter(|x| **x).count())
+            .sum()
+    }
+}
+
+impl Image {
+    fn get_pixel_index(pixels: &[Vec<bool>], x: usize, y: usize) -> usize {
         let index: usize = vec![
             pixels[y - 1][x - 1],
             pixels[y - 1][x],
@@ -86,55 +177,254 @@ impl std::fmt::Display for Image {
     }
 }
 
-fn read_input(path: &str) -> (Algorithm, Image) {
-    let f = File::open(path).unwrap();
-    let reader = BufReader::new(f);
-    let lines: std::io::Result<Vec<String>> = reader.lines().collect();
-    let lines = lines.unwrap();
+fn parse_pixels(line: &str, line_no: usize) -> Result<Vec<bool>, Error> {
+    line.chars()
+        .enumerate()
+        .map(|(i, c)| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(Error::InvalidPixel {
+                line: line_no,
+                column: i + 1,
+                found: c,
+            }),
+        })
+        .collect()
+}
 
-    let mut lines = lines.into_iter();
-    let alg_line = lines.next().unwrap();
+fn parse_input(s: &str) -> Result<(Algorithm, Image), Error> {
+    let mut lines = s.lines().enumerate().map(|(i, l)| (i + 1, l));
 
-    let alg: Algorithm = alg_line.chars().map(|c| c == '#').collect();
+    let (line_no, alg_line) = lines.next().ok_or(Error::MissingAlgorithm)?;
+    let alg = parse_pixels(alg_line, line_no)?;
+    if alg.len() != ALGORITHM_LENGTH {
+        return Err(Error::AlgorithmLength(alg.len()));
+    }
 
-    lines.next();
+    match lines.next() {
+        Some((_, "")) => {}
+        Some((line_no, _)) => return Err(Error::MissingSeparator(line_no)),
+        None => return Err(Error::EmptyImage),
+    }
 
-    let image_lines = lines
-        .filter(|l| !l.is_empty())
-        .map(|line| line.chars().map(|c| c == '#').collect())
-        .collect();
+    let mut pixels: Vec<Vec<bool>> = vec![];
+    for (line_no, line) in lines.filter(|(_, l)| !l.is_empty()) {
+        let row = parse_pixels(line, line_no)?;
+
+        if let Some(first) = pixels.first() {
+            if row.len() != first.len() {
+                return Err(Error::RowLength {
+                    line: line_no,
+                    expected: first.len(),
+                    found: row.len(),
+                });
+            }
+        }
+
+        pixels.push(row);
+    }
+
+    if pixels.is_empty() || pixels[0].is_empty() {
+        return Err(Error::EmptyImage);
+    }
 
     let image = Image {
         fill_pixels: false,
-        pixels: image_lines,
+        pixels,
     };
 
-    (alg, image)
+    Ok((alg, image))
 }
 
-fn main() {
-    let (algorithm, mut image) = read_input("day20/input.txt");
+fn read_input(path: &str) -> Result<(Algorithm, Image), Error> {
+    let s = std::fs::read_to_string(path).map_err(Error::Io)?;
+    parse_input(&s)
+}
 
-    {
-        let image = image.enhanced(&algorithm).enhanced(&algorithm);
-        let x: usize = image
-            .pixels
-            .iter()
-            .map(|row| row.iter().filter(|x| **x).count())
-            .sum();
+/// Whether a lit background stays lit, which makes the number of lit pixels
+/// infinite after every step.
+fn background_stays_lit(alg: &Algorithm) -> bool {
+    alg[0] && alg[ALGORITHM_LENGTH - 1]
+}
+
+fn enhance_n<I: Enhance>(alg: &Algorithm, mut image: I, steps: usize) -> I {
+    for _ in 0..steps {
+        image = image.enhanced(alg);
+    }
+    image
+}
+
+fn bench<I: Enhance>(name: &str, alg: &Algorithm, pixels: &[Vec<bool>], steps: usize) {
+    let start = Instant::now();
+    let image = enhance_n(alg, I::from_pixels(pixels), steps);
+
+    println!(
+        "{:<8} {:>4} steps: {:>12.3?}, lit pixels: {}",
+        name,
+        steps,
+        start.elapsed(),
+        image.lit_pixels()
+    );
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let record = animation::Options::from_args(&mut args)?;
+
+    let input_file = args
+        .iter()
+        .find(|a| !a.starts_with("--"))
+        .map_or("day20/input.txt", String::as_str);
+
+    let (algorithm, image) = read_input(input_file)?;
+    if background_stays_lit(&algorithm) {
+        eprintln!("warning: the algorithm lights the infinite background and keeps it lit, lit pixel counts only cover the image bounds");
+    }
 
-        println!("Part1 - lit pixels: {}", x)
+    if args.iter().any(|a| a == "--bench") {
+        for steps in [50, 500] {
+            bench::<Image>("grid", &algorithm, &image.pixels, steps);
+            bench::<BitImage>("bits", &algorithm, &image.pixels, steps);
+            bench::<SparseImage>("sparse", &algorithm, &image.pixels, steps);
+        }
+        return Ok(());
     }
 
-    for _ in 0..50 {
+    let mut recorder = record.as_ref().map(|r| r.recorder(&PALETTE)).transpose()?;
+
+    let mut image = BitImage::from_pixels(&image.pixels);
+    for step in 1..=50 {
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.record(&image);
+        }
+
         image = image.enhanced(&algorithm);
+
+        if step == 2 {
+            println!("Part1 - lit pixels: {}", image.lit_pixels());
+        }
     }
+    println!("Part2 - lit pixels: {}", image.lit_pixels());
 
-    let x: usize = image
-        .pixels
-        .iter()
-        .map(|row| row.iter().filter(|x| **x).count())
-        .sum();
+    if let (Some(record), Some(mut recorder)) = (record, recorder) {
+        recorder.record(&image);
+        record.write(&recorder, "day20_")?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_implementations_example() {
+    let (alg, image) = read_input("input_test.txt").unwrap();
+
+    for (steps, lit) in [(2, 35), (50, 3351)] {
+        assert_eq!(
+            enhance_n(&alg, Image::from_pixels(&image.pixels), steps).lit_pixels(),
+            lit
+        );
+        assert_eq!(
+            enhance_n(&alg, BitImage::from_pixels(&image.pixels), steps).lit_pixels(),
+            lit
+        );
+        assert_eq!(
+            enhance_n(&alg, SparseImage::from_pixels(&image.pixels), steps).lit_pixels(),
+            lit
+        );
+    }
+}
+
+#[test]
+fn test_implementations_flipping_background() {
+    let (alg, image) = read_input("input.txt").unwrap();
+    assert!(alg[0] && !alg[511]);
+
+    for steps in 1..=4 {
+        let lit = enhance_n(&alg, Image::from_pixels(&image.pixels), steps).lit_pixels();
+        assert_eq!(
+            enhance_n(&alg, BitImage::from_pixels(&image.pixels), steps).lit_pixels(),
+            lit
+        );
+        assert_eq!(
+            enhance_n(&alg, SparseImage::from_pixels(&image.pixels), steps).lit_pixels(),
+            lit
+        );
+    }
+}
+
+#[test]
+fn test_non_square_image() {
+    // Every pixel keeps the value of the centre of its window.
+    let identity: Algorithm = (0..ALGORITHM_LENGTH).map(|i| i & 0b10000 != 0).collect();
+    let pixels = vec![vec![false, true, false, false, true]];
+
+    let image = Image::from_pixels(&pixels).enhanced(&identity);
+    assert_eq!(
+        image.pixels,
+        vec![
+            vec![false; 7],
+            vec![false, false, true, false, false, true, false],
+            vec![false; 7],
+        ]
+    );
+
+    let (alg, _) = read_input("input_test.txt").unwrap();
+    let (flipping_alg, _) = read_input("input.txt").unwrap();
+    let pixels = vec![
+        vec![true, false, false, true, true, false, true],
+        vec![false, false, true, true, false, false, false],
+        vec![true, true, false, false, false, true, true],
+    ];
+
+    for alg in [alg, flipping_alg] {
+        for steps in 1..=4 {
+            let lit = enhance_n(&alg, Image::from_pixels(&pixels), steps).lit_pixels();
+            assert_eq!(
+                enhance_n(&alg, BitImage::from_pixels(&pixels), steps).lit_pixels(),
+                lit
+            );
+            assert_eq!(
+                enhance_n(&alg, SparseImage::from_pixels(&pixels), steps).lit_pixels(),
+                lit
+            );
+        }
+    }
+}
+
+#[test]
+fn test_parse_input_errors() {
+    let alg = "#".repeat(ALGORITHM_LENGTH);
+
+    assert!(matches!(
+        parse_input("#.#\n\n#.\n.#"),
+        Err(Error::AlgorithmLength(3))
+    ));
+    assert!(matches!(
+        parse_input(&format!("{}\n\n#.#\n.#", alg)),
+        Err(Error::RowLength {
+            line: 4,
+            expected: 3,
+            found: 2
+        })
+    ));
+    assert!(matches!(
+        parse_input(&format!("{}\n\n#.#\n.x.", alg)),
+        Err(Error::InvalidPixel {
+            line: 4,
+            column: 2,
+            found: 'x'
+        })
+    ));
+    assert!(matches!(
+        parse_input(&format!("{}\n#.#", alg)),
+        Err(Error::MissingSeparator(2))
+    ));
+    assert!(matches!(
+        parse_input(&format!("{}\n\n", alg)),
+        Err(Error::EmptyImage)
+    ));
 
-    println!("Part2 - lit pixels: {}", x);
+    let (alg, image) = parse_input(&format!("{}\n\n#.#\n.#.", alg)).unwrap();
+    assert!(background_stays_lit(&alg));
+    assert_eq!((image.pixels[0].len(), image.pixels.len()), (3, 2));
 }
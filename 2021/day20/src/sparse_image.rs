@@ -0,0 +1,96 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{Algorithm, Enhance};
+
+/// Offsets of the pixels making up an index, from the least significant bit.
+const NEIGHBOURS: [(i64, i64); 9] = [
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (1, 0),
+    (0, 0),
+    (-1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+/// Image stored as the set of pixels that differ from the infinite background,
+/// so the work per step depends on the number of such pixels and not on the
+/// size of the image.
+///
+/// That only pays off while few pixels differ from the background. With an
+/// algorithm that flips the background every step, like the puzzle input,
+/// the differing pixels grow with the image area and every step rebuilds a
+/// map with up to nine entries per stored pixel: 500 steps on the input take
+/// about 25 s, against 1.7 s for `Image` and 0.4 s for `BitImage`.
+pub struct SparseImage {
+    fill_pixels: bool,
+    min: (i64, i64),
+    max: (i64, i64),
+    pixels: FxHashSet<(i64, i64)>,
+}
+
+impl Enhance for SparseImage {
+    fn from_pixels(pixels: &[Vec<bool>]) -> Self {
+        let width = pixels.first().map_or(0, |row| row.len());
+
+        let lit = pixels.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, p)| **p)
+                .map(move |(x, _)| (x as i64, y as i64))
+        });
+
+        Self {
+            fill_pixels: false,
+            min: (0, 0),
+            max: (width as i64 - 1, pixels.len() as i64 - 1),
+            pixels: lit.collect(),
+        }
+    }
+
+    fn enhanced(&self, alg: &Algorithm) -> Self {
+        let fill_pixels = match self.fill_pixels {
+            false => alg[0],
+            true => alg[511],
+        };
+
+        // Every stored pixel sets its bit in the index of the nine pixels around
+        // it. Pixels that are not reached only see the background, so they
+        // become the new background and are not stored.
+        let mut masks: FxHashMap<(i64, i64), usize> = FxHashMap::default();
+        masks.reserve(self.pixels.len() * 2);
+        for &(x, y) in self.pixels.iter() {
+            for (bit, (dx, dy)) in NEIGHBOURS.iter().enumerate() {
+                *masks.entry((x - dx, y - dy)).or_default() |= 1 << bit;
+            }
+        }
+
+        let fill_index = match self.fill_pixels {
+            false => 0,
+            true => 511,
+        };
+        let pixels = masks
+            .into_iter()
+            .filter(|&(_, mask)| alg[mask ^ fill_index] != fill_pixels)
+            .map(|(p, _)| p)
+            .collect();
+
+        Self {
+            fill_pixels,
+            min: (self.min.0 - 1, self.min.1 - 1),
+            max: (self.max.0 + 1, self.max.1 + 1),
+            pixels,
+        }
+    }
+
+    fn lit_pixels(&self) -> usize {
+        let area = ((self.max.0 - self.min.0 + 1) * (self.max.1 - self.min.1 + 1)) as usize;
+
+        match self.fill_pixels {
+            false => self.pixels.len(),
+            true => area - self.pixels.len(),
+        }
+    }
+}
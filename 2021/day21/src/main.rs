@@ -1,10 +1,144 @@
-use std::collections::{BinaryHeap, HashMap};
+use std::{collections::HashMap, str::FromStr};
+
+#[derive(Debug)]
+enum Error {
+    Io(std::io::Error),
+    InvalidLine(usize, String),
+    InvalidOption(String),
+    InvalidConfig(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {}", e),
+            Self::InvalidLine(line, s) => write!(f, "line {}: cannot parse {:?}", line, s),
+            Self::InvalidOption(s) => write!(f, "invalid option: {}", s),
+            Self::InvalidConfig(s) => write!(f, "invalid game: {}", s),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Rules shared by the practice game and the Dirac dice game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GameConfig {
+    board_size: usize,
+    rolls_per_turn: usize,
+    starts: Vec<usize>,
+
+    die_faces: usize,
+    winning_score: usize,
+
+    quantum_die_faces: usize,
+    quantum_winning_score: usize,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            board_size: 10,
+            rolls_per_turn: 3,
+            starts: vec![],
+            die_faces: 100,
+            winning_score: 1000,
+            quantum_die_faces: 3,
+            quantum_winning_score: 21,
+        }
+    }
+}
+
+impl GameConfig {
+    /// Sets an option by the name used on the command line, without the
+    /// leading dashes.
+    fn set(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        let invalid = || Error::InvalidOption(format!("{} {}", name, value));
+        let number = || value.trim().parse::<usize>().map_err(|_| invalid());
+
+        match name {
+            "board" => self.board_size = number()?,
+            "rolls" => self.rolls_per_turn = number()?,
+            "die-faces" => self.die_faces = number()?,
+            "target" => self.winning_score = number()?,
+            "quantum-faces" => self.quantum_die_faces = number()?,
+            "quantum-target" => self.quantum_winning_score = number()?,
+            "start" => {
+                self.starts = value
+                    .split(',')
+                    .map(|s| s.trim().parse().map_err(|_| invalid()))
+                    .collect::<Result<_, _>>()?
+            }
+            _ => return Err(invalid()),
+        }
+
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let check = |ok: bool, msg: &str| match ok {
+            true => Ok(()),
+            false => Err(Error::InvalidConfig(msg.to_owned())),
+        };
+
+        check(self.board_size > 0, "board size must be positive")?;
+        check(self.rolls_per_turn > 0, "rolls per turn must be positive")?;
+        check(self.die_faces > 0, "die must have faces")?;
+        check(self.quantum_die_faces > 0, "quantum die must have faces")?;
+        check(!self.starts.is_empty(), "no players")?;
+        check(
+            self.starts
+                .iter()
+                .all(|s| (1..=self.board_size).contains(s)),
+            "starting positions must be on the board",
+        )
+    }
+}
+
+/// Parses the puzzle input: one `Player N starting position: P` line per
+/// player, optionally mixed with `<option>: <value>` lines using the command
+/// line option names.
+impl FromStr for GameConfig {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut config = Self::default();
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let invalid = || Error::InvalidLine(i + 1, line.to_owned());
+            let (name, value) = line.split_once(':').ok_or_else(invalid)?;
+
+            match name.strip_suffix(" starting position") {
+                Some(_) => config
+                    .starts
+                    .push(value.trim().parse().map_err(|_| invalid())?),
+                None => config.set(name.trim(), value)?,
+            }
+        }
+
+        Ok(config)
+    }
+}
 
 struct Dice {
     iter: Box<dyn Iterator<Item = usize>>,
     rolls: usize,
 }
 
+impl Dice {
+    fn deterministic(faces: usize) -> Self {
+        Self {
+            iter: Box::new((1..faces + 1).cycle()),
+            rolls: 0,
+        }
+    }
+}
+
 impl Iterator for Dice {
     type Item = usize;
 
@@ -14,257 +148,279 @@ impl Iterator for Dice {
     }
 }
 
+fn advance(position: usize, roll: usize, board_size: usize) -> usize {
+    (position - 1 + roll) % board_size + 1
+}
+
 struct Player {
-    state: Vec<i32>,
-    score: i32,
+    position: usize,
+    score: usize,
 }
 
 impl Player {
-    fn new(start: i32) -> Self {
+    fn new(start: usize) -> Self {
         Self {
-            state: (start..11).chain(1..start).collect(),
+            position: start,
             score: 0,
         }
     }
 
-    fn next_turn<T>(&mut self, dice: &mut T) -> bool
+    fn next_turn<T>(&mut self, dice: &mut T, config: &GameConfig) -> bool
     where
         T: Iterator<Item = usize>,
     {
-        let roll: usize = dice.take(3).sum();
-        let shift = roll % 10;
+        let roll: usize = dice.take(config.rolls_per_turn).sum();
 
-        self.state.rotate_left(shift);
-        let pos = self.state[0];
-        self.score += pos;
+        self.position = advance(self.position, roll, config.board_size);
+        self.score += self.position;
 
-        self.score >= 1000
+        self.score >= config.winning_score
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum Turn {
-    Player1,
-    Player2,
+#[derive(Debug, PartialEq, Eq)]
+struct PracticeResult {
+    winner: usize,
+    scores: Vec<usize>,
+    rolls: usize,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-struct GameState {
-    p1_score: usize,
-    p2_score: usize,
-
-    p1_state: usize,
-    p2_state: usize,
+impl PracticeResult {
+    /// The lowest losing score multiplied by the number of die rolls.
+    fn answer(&self) -> usize {
+        let loser_score = self
+            .scores
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != self.winner)
+            .map(|(_, s)| *s)
+            .min()
+            .unwrap_or(0);
 
-    turn: Turn,
+        loser_score * self.rolls
+    }
 }
 
-impl Ord for GameState {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        let self_min = vec![self.p1_score, self.p2_score]
-            .into_iter()
-            .min()
-            .unwrap();
-        let other_min = vec![other.p1_score, other.p2_score]
-            .into_iter()
-            .min()
-            .unwrap();
-        self_min.cmp(&other_min)
+fn play_practice(config: &GameConfig) -> PracticeResult {
+    let mut dice = Dice::deterministic(config.die_faces);
+    let mut players: Vec<Player> = config.starts.iter().map(|s| Player::new(*s)).collect();
+
+    let winner = (0..players.len())
+        .cycle()
+        .find(|&i| players[i].next_turn(dice.by_ref(), config))
+        .unwrap();
+
+    PracticeResult {
+        winner,
+        scores: players.iter().map(|p| p.score).collect(),
+        rolls: dice.rolls,
     }
 }
 
-impl PartialOrd for GameState {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        let self_min = vec![self.p1_score, self.p2_score]
-            .into_iter()
-            .min()
-            .unwrap();
-        let other_min = vec![other.p1_score, other.p2_score]
-            .into_iter()
-            .min()
-            .unwrap();
-        match self_min.partial_cmp(&other_min) {
-            Some(core::cmp::Ordering::Equal) => {}
-            ord => return ord,
+/// Distinct sums of `rolls` throws of a die with `faces` faces, with the
+/// number of universes producing each sum.
+fn roll_outcomes(faces: usize, rolls: usize) -> Vec<(usize, u128)> {
+    let mut counts = vec![1u128];
+
+    for _ in 0..rolls {
+        let mut next = vec![0; counts.len() + faces];
+        for (sum, count) in counts.iter().enumerate() {
+            for face in 1..faces + 1 {
+                next[sum + face] += count;
+            }
         }
+        counts = next;
+    }
+
+    counts
+        .into_iter()
+        .enumerate()
+        .filter(|(_, count)| *count > 0)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct GameState {
+    positions: Vec<usize>,
+    scores: Vec<usize>,
+    turn: usize,
+}
+
+struct QuantumGame<'a> {
+    config: &'a GameConfig,
+    outcomes: Vec<(usize, u128)>,
+    memo: HashMap<GameState, Vec<u128>>,
+}
 
-        let self_max = vec![self.p1_score, self.p2_score]
-            .into_iter()
-            .max()
-            .unwrap();
-        let other_max = vec![other.p1_score, other.p2_score]
-            .into_iter()
-            .max()
-            .unwrap();
-        match self_max.partial_cmp(&other_max) {
-            Some(core::cmp::Ordering::Equal) => {}
-            ord => return ord,
+impl<'a> QuantumGame<'a> {
+    fn new(config: &'a GameConfig) -> Self {
+        Self {
+            config,
+            outcomes: roll_outcomes(config.quantum_die_faces, config.rolls_per_turn),
+            memo: HashMap::new(),
         }
+    }
 
-        match self.p1_state.partial_cmp(&other.p1_state) {
-            Some(core::cmp::Ordering::Equal) => {}
-            ord => return ord,
+    /// Counts the universes in which each player wins, starting from `state`.
+    fn wins(&mut self, state: &GameState) -> Vec<u128> {
+        if let Some(wins) = self.memo.get(state) {
+            return wins.clone();
         }
-        match self.p2_state.partial_cmp(&other.p2_state) {
-            Some(core::cmp::Ordering::Equal) => {}
-            ord => return ord,
+
+        let players = state.positions.len();
+        let mut wins = vec![0; players];
+
+        for i in 0..self.outcomes.len() {
+            let (roll, count) = self.outcomes[i];
+
+            let mut next = state.clone();
+            next.positions[state.turn] =
+                advance(state.positions[state.turn], roll, self.config.board_size);
+            next.scores[state.turn] += next.positions[state.turn];
+            next.turn = (state.turn + 1) % players;
+
+            if next.scores[state.turn] >= self.config.quantum_winning_score {
+                wins[state.turn] += count;
+                continue;
+            }
+
+            for (w, n) in wins.iter_mut().zip(self.wins(&next)) {
+                *w += count * n;
+            }
         }
-        self.turn.partial_cmp(&other.turn)
+
+        self.memo.insert(state.clone(), wins.clone());
+        wins
     }
 }
 
-#[derive(Debug, Clone)]
-struct GameResults {
-    p1_wins: usize,
-    p2_wins: usize,
+/// Counts the universes in which each player wins the Dirac dice game.
+fn quantum_wins(config: &GameConfig) -> Vec<u128> {
+    let start = GameState {
+        positions: config.starts.clone(),
+        scores: vec![0; config.starts.len()],
+        turn: 0,
+    };
+
+    QuantumGame::new(config).wins(&start)
 }
 
-fn all_games() -> Vec<GameState> {
-    let mut games = vec![];
-
-    for turn in vec![Turn::Player1, Turn::Player2].iter() {
-        for p1_state in 1..11 {
-            for p2_state in 1..11 {
-                for p1_score in 0..32 {
-                    for p2_score in 0..31 {
-                        let game = GameState {
-                            p1_score,
-                            p2_score,
-                            p1_state,
-                            p2_state,
-                            turn: turn.clone(),
-                        };
-                        games.push(game);
-                    }
-                }
+fn load_config() -> Result<GameConfig, Error> {
+    let mut input_file = "day21/input.txt".to_owned();
+    let mut options = vec![];
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("--") {
+            Some(name) => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| Error::InvalidOption(format!("{} requires a value", arg)))?;
+                options.push((name.to_owned(), value));
             }
+            None => input_file = arg,
         }
     }
 
-    games
-}
-
-fn main() {
-    let p1_start = 5;
-    let p2_start = 10;
+    let mut config: GameConfig = std::fs::read_to_string(input_file)
+        .map_err(Error::Io)?
+        .parse()?;
+    for (name, value) in options {
+        config.set(&name, &value)?;
+    }
 
-    let mut dice = Dice {
-        iter: Box::new((1..101).cycle()),
-        rolls: 0,
-    };
-    let dice = dice.by_ref();
+    config.validate()?;
+    Ok(config)
+}
 
-    let mut p1 = Player::new(p1_start);
-    let mut p2 = Player::new(p2_start);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let config = load_config()?;
 
-    loop {
-        if p1.next_turn(dice) {
-            println!("P1 won - {}", p2.score * dice.rolls as i32);
-            break;
-        }
+    let practice = play_practice(&config);
+    println!("P{} won - {}", practice.winner + 1, practice.answer());
 
-        if p2.next_turn(dice) {
-            println!("P2 won - {}", p1.score * dice.rolls as i32);
-            break;
-        }
+    for (i, wins) in quantum_wins(&config).iter().enumerate() {
+        println!("P{} wins in {} universes", i + 1, wins);
     }
 
-    let mut winning_games: HashMap<GameState, GameResults> = HashMap::new();
+    Ok(())
+}
 
-    let mut games = BinaryHeap::new();
-    for game in all_games() {
-        games.push(game);
-    }
+#[test]
+fn test_practice_example() {
+    let config: GameConfig = "Player 1 starting position: 4\nPlayer 2 starting position: 8"
+        .parse()
+        .unwrap();
 
-    let rolls: Vec<usize> = (1..4)
-        .map(|x| {
-            (1..4)
-                .map(move |y| (1..4).map(move |z| x + y + z))
-                .flatten()
-        })
-        .flatten()
-        .collect();
-
-    while let Some(game) = games.pop() {
-        if game.p1_score >= 21 {
-            winning_games.insert(
-                game,
-                GameResults {
-                    p1_wins: 1,
-                    p2_wins: 0,
-                },
-            );
-            continue;
+    assert_eq!(
+        play_practice(&config),
+        PracticeResult {
+            winner: 0,
+            scores: vec![1000, 745],
+            rolls: 993,
         }
+    );
+    assert_eq!(play_practice(&config).answer(), 739785);
+}
 
-        if game.p2_score >= 21 {
-            winning_games.insert(
-                game,
-                GameResults {
-                    p1_wins: 0,
-                    p2_wins: 1,
-                },
-            );
-            continue;
-        }
+#[test]
+fn test_parse_config() {
+    let config: GameConfig = "Player 1 starting position: 3\nboard: 7\nPlayer 2 starting position: 6\nPlayer 3 starting position: 1\nquantum-target: 9"
+        .parse()
+        .unwrap();
 
-        match game.turn {
-            Turn::Player1 => {
-                let mut result = GameResults {
-                    p1_wins: 0,
-                    p2_wins: 0,
-                };
-
-                for roll in rolls.iter() {
-                    let new_state = (game.p1_state - 1 + roll) % 10 + 1;
-                    let mut g = game.clone();
-                    g.p1_state = new_state;
-                    g.p1_score += new_state;
-                    g.turn = Turn::Player2;
-
-                    let res = winning_games.get(&g).unwrap().clone();
-                    result.p1_wins += res.p1_wins;
-                    result.p2_wins += res.p2_wins;
-                }
-
-                winning_games.insert(game.clone(), result);
-            }
-            Turn::Player2 => {
-                let mut result = GameResults {
-                    p1_wins: 0,
-                    p2_wins: 0,
-                };
-
-                for roll in rolls.iter() {
-                    let new_state = (game.p2_state - 1 + roll) % 10 + 1;
-                    let mut g = game.clone();
-                    g.p2_state = new_state;
-                    g.p2_score += new_state;
-                    g.turn = Turn::Player1;
-
-                    let res = winning_games.get(&g).unwrap().clone();
-                    result.p1_wins += res.p1_wins;
-                    result.p2_wins += res.p2_wins;
-                }
-
-                winning_games.insert(game.clone(), result);
-            }
-        }
-    }
+    assert_eq!(config.starts, vec![3, 6, 1]);
+    assert_eq!(config.board_size, 7);
+    assert_eq!(config.quantum_winning_score, 9);
+    assert!(config.validate().is_ok());
+
+    assert!("Player 1 starting position: x"
+        .parse::<GameConfig>()
+        .is_err());
+    assert!("board size 10".parse::<GameConfig>().is_err());
+    assert!("Player 1 starting position: 11"
+        .parse::<GameConfig>()
+        .unwrap()
+        .validate()
+        .is_err());
+}
 
-    //for (game, results) in winning_games {
-    //    println!("{:?}: {:?}", game, results);
-    //}
-
-    let result = winning_games
-        .get(&GameState {
-            p1_score: 0,
-            p2_score: 0,
-            p1_state: p1_start as usize,
-            p2_state: p2_start as usize,
-            turn: Turn::Player1,
-        })
+#[test]
+fn test_roll_outcomes() {
+    assert_eq!(
+        roll_outcomes(3, 3),
+        vec![(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)]
+    );
+    assert_eq!(
+        roll_outcomes(6, 1),
+        (1..7).map(|s| (s, 1)).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_quantum_example() {
+    let config: GameConfig = "Player 1 starting position: 4\nPlayer 2 starting position: 8"
+        .parse()
         .unwrap();
 
-    println!("{:?}", result);
+    assert_eq!(
+        quantum_wins(&config),
+        vec![444356092776315, 341960390180808]
+    );
+}
+
+#[test]
+fn test_quantum_single_roll() {
+    // Player 1 wins at once by rolling 1, otherwise player 2 wins by rolling 1
+    // and player 1 wins with any roll after that.
+    let config = GameConfig {
+        board_size: 2,
+        rolls_per_turn: 1,
+        starts: vec![1, 1],
+        quantum_die_faces: 2,
+        quantum_winning_score: 2,
+        ..Default::default()
+    };
+
+    assert_eq!(quantum_wins(&config), vec![3, 1]);
 }
@@ -0,0 +1,226 @@
+use std::{num::IntErrorKind, str::FromStr};
+
+use crate::ParseError;
+
+/// Axis-aligned box of cubes with inclusive bounds on every axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    pub x: (i64, i64),
+    pub y: (i64, i64),
+    pub z: (i64, i64),
+}
+
+fn overlap(a: (i64, i64), b: (i64, i64)) -> Option<(i64, i64)> {
+    let range = (a.0.max(b.0), a.1.min(b.1));
+    match range.0 <= range.1 {
+        true => Some(range),
+        false => None,
+    }
+}
+
+impl Cuboid {
+    pub fn volume(&self) -> i64 {
+        (self.x.1 - self.x.0 + 1) * (self.y.1 - self.y.0 + 1) * (self.z.1 - self.z.0 + 1)
+    }
+
+    pub fn contains(&self, (x, y, z): (i64, i64, i64)) -> bool {
+        (self.x.0..=self.x.1).contains(&x)
+            && (self.y.0..=self.y.1).contains(&y)
+            && (self.z.0..=self.z.1).contains(&z)
+    }
+
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        Some(Cuboid {
+            x: overlap(self.x, other.x)?,
+            y: overlap(self.y, other.y)?,
+            z: overlap(self.z, other.z)?,
+        })
+    }
+
+    /// Splits the part of `self` outside `other` into at most six disjoint
+    /// cuboids: slabs below and above `other` on the x axis, then on the y axis
+    /// within its x range, then on the z axis within its x and y ranges.
+    pub fn subtract(&self, other: &Cuboid) -> Vec<Cuboid> {
+        let i = match self.intersection(other) {
+            Some(i) => i,
+            None => return vec![*self],
+        };
+
+        let mut pieces = Vec::with_capacity(6);
+        let mut push = |x: (i64, i64), y: (i64, i64), z: (i64, i64)| {
+            if x.0 <= x.1 && y.0 <= y.1 && z.0 <= z.1 {
+                pieces.push(Cuboid { x, y, z });
+            }
+        };
+
+        push((self.x.0, i.x.0 - 1), self.y, self.z);
+        push((i.x.1 + 1, self.x.1), self.y, self.z);
+        push(i.x, (self.y.0, i.y.0 - 1), self.z);
+        push(i.x, (i.y.1 + 1, self.y.1), self.z);
+        push(i.x, i.y, (self.z.0, i.z.0 - 1));
+        push(i.x, i.y, (i.z.1 + 1, self.z.1));
+
+        pieces
+    }
+}
+
+impl std::fmt::Display for Cuboid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "x={}..{},y={}..{},z={}..{}",
+            self.x.0, self.x.1, self.y.0, self.y.1, self.z.0, self.z.1
+        )
+    }
+}
+
+fn parse_coordinate(s: &str) -> Result<i64, ParseError> {
+    s.parse()
+        .map_err(|e: std::num::ParseIntError| match e.kind() {
+            IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                ParseError::CoordinateOverflow(s.to_owned())
+            }
+            _ => ParseError::InvalidCoordinate(s.to_owned()),
+        })
+}
+
+/// Parses `<axis>=a..b` and checks that the range is not reversed.
+fn parse_range(s: &str, axis: char) -> Result<(i64, i64), ParseError> {
+    let range = s
+        .strip_prefix(axis)
+        .and_then(|r| r.strip_prefix('='))
+        .and_then(|r| r.split_once(".."))
+        .ok_or_else(|| ParseError::InvalidRange(s.to_owned()))?;
+    let range = (parse_coordinate(range.0)?, parse_coordinate(range.1)?);
+
+    match range.0 <= range.1 {
+        true => Ok(range),
+        false => Err(ParseError::ReversedRange(axis, range.0, range.1)),
+    }
+}
+
+/// Parses `x=a..b,y=c..d,z=e..f`.
+impl FromStr for Cuboid {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ranges = s.split(',');
+        let mut next_range = |axis| parse_range(ranges.next().unwrap_or_default(), axis);
+
+        let cuboid = Self {
+            x: next_range('x')?,
+            y: next_range('y')?,
+            z: next_range('z')?,
+        };
+        if let Some(rest) = ranges.next() {
+            return Err(ParseError::InvalidRange(rest.to_owned()));
+        }
+
+        // Every length and the volume must fit, so that the volume of any
+        // cuboid cut from this one can be computed. Sums of volumes can still
+        // go past `i64::MAX`, `Reactor` counts them in `i128`.
+        let length = |(a, b): (i64, i64)| b.checked_sub(a).and_then(|l| l.checked_add(1));
+        [cuboid.x, cuboid.y, cuboid.z]
+            .into_iter()
+            .try_fold(1i64, |volume, range| volume.checked_mul(length(range)?))
+            .ok_or_else(|| ParseError::CoordinateOverflow(s.to_owned()))?;
+
+        Ok(cuboid)
+    }
+}
+
+#[test]
+fn test_intersection() {
+    let a = Cuboid {
+        x: (0, 9),
+        y: (0, 9),
+        z: (0, 9),
+    };
+    let b = Cuboid {
+        x: (5, 14),
+        y: (-5, 4),
+        z: (9, 9),
+    };
+
+    assert_eq!(
+        a.intersection(&b),
+        Some(Cuboid {
+            x: (5, 9),
+            y: (0, 4),
+            z: (9, 9)
+        })
+    );
+    assert_eq!(a.intersection(&Cuboid { x: (10, 11), ..a }), None);
+}
+
+#[test]
+fn test_subtract() {
+    let a = Cuboid {
+        x: (0, 9),
+        y: (0, 9),
+        z: (0, 9),
+    };
+    let b = Cuboid {
+        x: (3, 5),
+        y: (-2, 4),
+        z: (7, 12),
+    };
+
+    let pieces = a.subtract(&b);
+    let volume: i64 = pieces.iter().map(|p| p.volume()).sum();
+    assert_eq!(volume, a.volume() - a.intersection(&b).unwrap().volume());
+
+    for (i, p) in pieces.iter().enumerate() {
+        assert_eq!(p.intersection(&b), None);
+        for q in pieces[i + 1..].iter() {
+            assert_eq!(p.intersection(q), None);
+        }
+    }
+
+    assert_eq!(a.subtract(&a), vec![]);
+}
+
+#[test]
+fn test_parse_cuboid() {
+    let s = "x=-5..47,y=-31..22,z=-19..33";
+    let cuboid: Cuboid = s.parse().unwrap();
+
+    assert_eq!(
+        cuboid,
+        Cuboid {
+            x: (-5, 47),
+            y: (-31, 22),
+            z: (-19, 33)
+        }
+    );
+    assert_eq!(cuboid.to_string(), s);
+    assert!(cuboid.contains((-5, 22, 0)));
+    assert!(!cuboid.contains((-6, 22, 0)));
+
+    assert_eq!(
+        "x=1..0,y=0..0,z=0..0".parse::<Cuboid>(),
+        Err(ParseError::ReversedRange('x', 1, 0))
+    );
+    assert_eq!(
+        "x=0..0,z=0..0,y=0..0".parse::<Cuboid>(),
+        Err(ParseError::InvalidRange("z=0..0".to_owned()))
+    );
+    assert_eq!(
+        "x=0..0,y=0..0".parse::<Cuboid>(),
+        Err(ParseError::InvalidRange("".to_owned()))
+    );
+    assert_eq!(
+        "x=0..0,y=0..0,z=0..a".parse::<Cuboid>(),
+        Err(ParseError::InvalidCoordinate("a".to_owned()))
+    );
+    assert_eq!(
+        "x=0..99999999999999999999,y=0..0,z=0..0".parse::<Cuboid>(),
+        Err(ParseError::CoordinateOverflow(
+            "99999999999999999999".to_owned()
+        ))
+    );
+    assert!(matches!(
+        "x=-4000000000..4000000000,y=-4000000000..4000000000,z=0..0".parse::<Cuboid>(),
+        Err(ParseError::CoordinateOverflow(_))
+    ));
+}
@@ -1,158 +1,184 @@
-use regex::Regex;
-use std::{collections::BTreeSet, str::FromStr};
+use std::str::FromStr;
 
+use cuboid::Cuboid;
+use reactor::Reactor;
+
+mod cuboid;
 mod inputs;
+mod reactor;
+
+/// Region of the reactor covered by the initialization procedure.
+const INITIALIZATION_REGION: Cuboid = Cuboid {
+    x: (-50, 50),
+    y: (-50, 50),
+    z: (-50, 50),
+};
 
 #[derive(Debug, PartialEq, Eq)]
-struct Step {
-    on: bool,
-    x: (i32, i32),
-    y: (i32, i32),
-    z: (i32, i32),
+pub enum ParseError {
+    UnknownAction(String),
+    InvalidRange(String),
+    InvalidCoordinate(String),
+    CoordinateOverflow(String),
+    ReversedRange(char, i64, i64),
 }
 
-impl FromStr for Step {
-    type Err = Box<dyn std::error::Error>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let re = Regex::new(r"^(.+) x=(.+)\.\.(.+),y=(.+)\.\.(.+),z=(.+)\.\.(.+)$").unwrap();
-        let captures = re.captures(s).unwrap();
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownAction(s) => write!(f, "unknown action {:?}", s),
+            Self::InvalidRange(s) => write!(f, "invalid range {:?}", s),
+            Self::InvalidCoordinate(s) => write!(f, "invalid coordinate {:?}", s),
+            Self::CoordinateOverflow(s) => write!(f, "coordinates too large in {:?}", s),
+            Self::ReversedRange(axis, from, to) => {
+                write!(f, "reversed range {}={}..{}", axis, from, to)
+            }
+        }
+    }
+}
 
-        let on = &captures[1] == "on";
+impl std::error::Error for ParseError {}
 
-        let step = Self {
-            on: on,
-            x: (captures[2].parse().unwrap(), captures[3].parse().unwrap()),
-            y: (captures[4].parse().unwrap(), captures[5].parse().unwrap()),
-            z: (captures[6].parse().unwrap(), captures[7].parse().unwrap()),
-        };
+#[derive(Debug)]
+enum Error {
+    Io(std::io::Error),
+    Parse { line: usize, error: ParseError },
+}
 
-        Ok(step)
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {}", e),
+            Self::Parse { line, error } => write!(f, "line {}: {}", line, error),
+        }
     }
 }
 
+impl std::error::Error for Error {}
+
 #[derive(Debug, PartialEq, Eq)]
-struct CompressionMap {
-    x: Vec<i32>,
-    y: Vec<i32>,
-    z: Vec<i32>,
+struct Step {
+    on: bool,
+    x: (i64, i64),
+    y: (i64, i64),
+    z: (i64, i64),
 }
 
-fn load_steps(s: &str) -> Vec<Step> {
-    s.split('\n').map(|s| s.parse::<Step>().unwrap()).collect()
+impl Step {
+    fn cuboid(&self) -> Cuboid {
+        Cuboid {
+            x: self.x,
+            y: self.y,
+            z: self.z,
+        }
+    }
 }
 
-fn calculate_cube(initial: bool, steps: &Vec<Step>) -> BTreeSet<(u16, u16, u16)> {
-    let mut cubes_on: BTreeSet<(u16, u16, u16)> = BTreeSet::new();
-
-    for (i, step) in steps.iter().enumerate() {
-        println!("{}/{}, {}", i + 1, steps.len(), cubes_on.len());
+impl FromStr for Step {
+    type Err = ParseError;
 
-        if initial
-            && (step.x.0 < -50
-                || step.x.1 > 50
-                || step.y.0 < -50
-                || step.y.1 > 50
-                || step.z.0 < -50
-                || step.z.1 > 50)
-        {
-            continue;
-        }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (action, cuboid) = s
+            .split_once(' ')
+            .ok_or_else(|| ParseError::UnknownAction(s.to_owned()))?;
+
+        let on = match action {
+            "on" => true,
+            "off" => false,
+            _ => return Err(ParseError::UnknownAction(action.to_owned())),
+        };
+        let cuboid: Cuboid = cuboid.parse()?;
 
-        let mut f: Box<dyn FnMut((u16, u16, u16))> = match step.on {
-            true => Box::new(|x| {
-                cubes_on.insert(x);
-            }),
-            false => Box::new(|x| {
-                cubes_on.remove(&x);
-            }),
+        let step = Self {
+            on,
+            x: cuboid.x,
+            y: cuboid.y,
+            z: cuboid.z,
         };
 
-        for x in step.x.0..step.x.1 + 1 {
-            for y in step.y.0..step.y.1 + 1 {
-                for z in step.z.0..step.z.1 + 1 {
-                    f((x as u16, y as u16, z as u16));
-                }
-            }
-        }
+        Ok(step)
     }
+}
 
-    cubes_on
+/// Parses reboot steps, one per line, skipping empty lines.
+fn load_steps(s: &str) -> Result<Vec<Step>, Error> {
+    s.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            line.trim()
+                .parse()
+                .map_err(|error| Error::Parse { line: i + 1, error })
+        })
+        .collect()
 }
 
-fn compress_steps(steps: &Vec<Step>) -> (Vec<Step>, CompressionMap) {
-    let mut mappings = CompressionMap {
-        x: vec![1],
-        y: vec![1],
-        z: vec![1],
-    };
+/// Reads steps from `path`, or from stdin if the path is `-`.
+fn read_steps(path: &str) -> Result<Vec<Step>, Error> {
+    load_steps(&utils::read_input(path).map_err(Error::Io)?)
+}
 
-    let mut x_points: Vec<i32> = steps.iter().map(|s| vec![s.x.0, s.x.1]).flatten().collect();
-    x_points.sort();
-    x_points.dedup();
-    for window in x_points.windows(2) {
-        mappings.x.push(window[1] - window[0] - 1);
-        mappings.x.push(1);
-    }
+fn parse_point(s: &str) -> Result<(i64, i64, i64), Box<dyn std::error::Error>> {
+    let coords: Vec<i64> = s
+        .split(',')
+        .map(|c| c.trim().parse())
+        .collect::<Result<_, _>>()?;
 
-    let mut y_points: Vec<i32> = steps.iter().map(|s| vec![s.y.0, s.y.1]).flatten().collect();
-    y_points.sort();
-    y_points.dedup();
-    for window in y_points.windows(2) {
-        mappings.y.push(window[1] - window[0] - 1);
-        mappings.y.push(1);
+    match coords[..] {
+        [x, y, z] => Ok((x, y, z)),
+        _ => Err(format!("expected x,y,z, got {}", s).into()),
     }
+}
 
-    let mut z_points: Vec<i32> = steps.iter().map(|s| vec![s.z.0, s.z.1]).flatten().collect();
-    z_points.sort();
-    z_points.dedup();
-    for window in z_points.windows(2) {
-        mappings.z.push(window[1] - window[0] - 1);
-        mappings.z.push(1);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut input_file = "day22/input.txt".to_owned();
+    let mut options = std::collections::HashMap::new();
+    let mut print_cuboids = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--cuboids" => print_cuboids = true,
+            "--after" | "--point" | "--region" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("{} requires a value", arg))?;
+                options.insert(arg, value);
+            }
+            _ => input_file = arg,
+        }
     }
+    let option = |name: &str| options.get(name);
 
-    let new_steps = steps
-        .iter()
-        .map(|step| Step {
-            on: step.on,
-            x: (
-                x_points.iter().position(|x| *x == step.x.0).unwrap() as i32 * 2,
-                x_points.iter().position(|x| *x == step.x.1).unwrap() as i32 * 2,
-            ),
-            y: (
-                y_points.iter().position(|x| *x == step.y.0).unwrap() as i32 * 2,
-                y_points.iter().position(|x| *x == step.y.1).unwrap() as i32 * 2,
-            ),
-            z: (
-                z_points.iter().position(|x| *x == step.z.0).unwrap() as i32 * 2,
-                z_points.iter().position(|x| *x == step.z.1).unwrap() as i32 * 2,
-            ),
-        })
-        .collect();
+    let steps = read_steps(&input_file)?;
+    let step_count = match option("--after") {
+        Some(n) => n.parse()?,
+        None => steps.len(),
+    };
 
-    (new_steps, mappings)
-}
+    let reactor = Reactor::from_steps(&steps[..step_count.min(steps.len())]);
 
-fn calculate_cubes_on(cubes: &BTreeSet<(u16, u16, u16)>, mapping: &CompressionMap) -> usize {
-    let mut count = 0;
+    println!("After {} steps", reactor.steps_applied());
+    println!("Part1: {}", reactor.count_on_in(&INITIALIZATION_REGION));
+    println!("Part2: {}", reactor.count_on());
 
-    for &(x, y, z) in cubes {
-        count += mapping.x[x as usize] as usize
-            * mapping.y[y as usize] as usize
-            * mapping.z[z as usize] as usize;
+    if let Some(point) = option("--point") {
+        let point = parse_point(point)?;
+        println!("{:?} is on: {}", point, reactor.is_on(point));
     }
 
-    count
-}
+    if let Some(region) = option("--region") {
+        let region: Cuboid = region.parse()?;
+        println!("On in {}: {}", region, reactor.count_on_in(&region));
+    }
 
-fn main() {
-    let steps = load_steps(inputs::INPUT);
-    let cubes_on = calculate_cube(true, &steps);
-    println!("Part1: {}", cubes_on.len());
+    if print_cuboids {
+        for cuboid in reactor.cuboids() {
+            println!("{}", cuboid);
+        }
+    }
 
-    let (compressed, mapping) = compress_steps(&steps);
-    let cubes = calculate_cube(false, &compressed);
-    println!("Part2: {}", calculate_cubes_on(&cubes, &mapping));
+    Ok(())
 }
 
 #[test]
@@ -171,14 +197,43 @@ fn test_step_from_str() {
     )
 }
 
+#[test]
+fn test_load_steps_errors() {
+    assert!(matches!(
+        load_steps("on x=0..1,y=0..1,z=0..1\n\ntoggle x=0..1,y=0..1,z=0..1"),
+        Err(Error::Parse {
+            line: 3,
+            error: ParseError::UnknownAction(_)
+        })
+    ));
+    assert!(matches!(
+        load_steps("off x=0..1,y=5..1,z=0..1"),
+        Err(Error::Parse {
+            line: 1,
+            error: ParseError::ReversedRange('y', 5, 1)
+        })
+    ));
+    assert!(matches!(
+        load_steps("on\noff x=0..1,y=0..1,z=0..1"),
+        Err(Error::Parse {
+            line: 1,
+            error: ParseError::UnknownAction(_)
+        })
+    ));
+    assert_eq!(load_steps("\non x=0..1,y=0..1,z=0..1\n").unwrap().len(), 1);
+}
+
 #[test]
 fn test_calc_cube() {
-    let steps = load_steps(inputs::PART1_TEST_INPUT);
-    assert_eq!(calculate_cube(true, &steps).len(), 590784);
+    let steps = load_steps(inputs::PART1_TEST_INPUT).unwrap();
+    assert_eq!(
+        Reactor::from_steps(&steps).count_on_in(&INITIALIZATION_REGION),
+        590784
+    );
 }
 
 #[test]
-fn test_compress_steps() {
+fn test_overlapping_steps() {
     let steps = vec![
         Step {
             on: true,
@@ -194,47 +249,76 @@ fn test_compress_steps() {
         },
     ];
 
-    assert_eq!(calculate_cube(false, &steps).len(), 206);
+    assert_eq!(Reactor::from_steps(&steps).count_on(), 206);
 
-    let (compressed, map) = compress_steps(&steps);
+    let steps = load_steps("on x=10..12,y=10..12,z=10..12\non x=11..13,y=11..13,z=11..13\noff x=9..11,y=9..11,z=9..11\non x=10..10,y=10..10,z=10..10").unwrap();
+    assert_eq!(Reactor::from_steps(&steps).count_on(), 39);
+}
 
-    assert_eq!(
-        compressed,
-        vec![
-            Step {
-                on: true,
-                x: (0, 4),
-                y: (2, 4),
-                z: (0, 2)
-            },
-            Step {
-                on: true,
-                x: (2, 6),
-                y: (0, 6),
-                z: (4, 6)
-            }
-        ]
-    );
+#[test]
+fn test_calc_cube_2() {
+    let steps = load_steps(inputs::PART2_TEST_INPUT).unwrap();
 
-    assert_eq!(
-        map,
-        CompressionMap {
-            x: vec![1, 3, 1, 4, 1, 0, 1],
-            y: vec![1, 0, 1, 2, 1, 0, 1],
-            z: vec![1, 0, 1, 2, 1, 1, 1],
-        }
-    );
+    let reactor = Reactor::from_steps(&steps);
 
-    let cubes = calculate_cube(false, &compressed);
-    assert_eq!(calculate_cubes_on(&cubes, &map), 206)
+    assert_eq!(reactor.count_on_in(&INITIALIZATION_REGION), 474140);
+    assert_eq!(reactor.count_on(), 2758514936282235);
 }
 
 #[test]
-fn test_calc_cube_2() {
-    let steps = load_steps(inputs::PART2_TEST_INPUT);
+fn test_reactor_incremental() {
+    let steps = load_steps("on x=10..12,y=10..12,z=10..12\non x=11..13,y=11..13,z=11..13\noff x=9..11,y=9..11,z=9..11\non x=10..10,y=10..10,z=10..10").unwrap();
+    let mut reactor = Reactor::new();
 
-    let (steps, mappings) = compress_steps(&steps);
+    let counts: Vec<i128> = steps
+        .iter()
+        .map(|step| {
+            reactor.apply(step);
+            reactor.count_on()
+        })
+        .collect();
+    assert_eq!(counts, vec![27, 46, 38, 39]);
+    assert_eq!(reactor.steps_applied(), 4);
+
+    assert!(reactor.is_on((10, 10, 10)));
+    assert!(!reactor.is_on((11, 11, 11)));
+    assert!(reactor.is_on((13, 13, 13)));
+    assert!(!reactor.is_on((14, 13, 13)));
+
+    let corner = Cuboid {
+        x: (12, 20),
+        y: (12, 20),
+        z: (12, 20),
+    };
+    assert_eq!(reactor.count_on_in(&corner), 8);
 
-    let cube = calculate_cube(false, &steps);
-    assert_eq!(calculate_cubes_on(&cube, &mappings), 2758514936282235);
+    let cuboids = reactor.cuboids();
+    assert_eq!(cuboids.iter().map(|c| c.volume()).sum::<i64>(), 39);
+    for (i, a) in cuboids.iter().enumerate() {
+        for b in cuboids[i + 1..].iter() {
+            assert_eq!(a.intersection(b), None);
+        }
+    }
+}
+
+#[test]
+fn test_count_past_i64() {
+    // Each cuboid is half of i64::MAX cubes, so three of them do not fit.
+    let steps = load_steps(
+        "on x=0..4611686018427387903,y=0..0,z=0..0\n\
+         on x=0..4611686018427387903,y=1..1,z=0..0\n\
+         on x=0..4611686018427387903,y=2..2,z=0..0",
+    )
+    .unwrap();
+    let reactor = Reactor::from_steps(&steps);
+
+    assert_eq!(reactor.count_on(), 3 * (1i128 << 62));
+    assert_eq!(
+        reactor.count_on_in(&Cuboid {
+            x: (0, i64::MAX),
+            y: (0, 1),
+            z: (0, 0),
+        }),
+        1i128 << 63
+    );
 }
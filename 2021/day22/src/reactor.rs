@@ -0,0 +1,66 @@
+use crate::{cuboid::Cuboid, Step};
+
+/// State of the reactor core, kept as a list of disjoint cuboids of cubes
+/// that are on.
+#[derive(Debug, Clone, Default)]
+pub struct Reactor {
+    cuboids: Vec<Cuboid>,
+    steps_applied: usize,
+}
+
+impl Reactor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_steps(steps: &[Step]) -> Self {
+        let mut reactor = Self::new();
+        for step in steps {
+            reactor.apply(step);
+        }
+        reactor
+    }
+
+    /// Cuts the cuboid of the step out of the cubes that are on, and adds it
+    /// back if the step turns cubes on.
+    pub fn apply(&mut self, step: &Step) {
+        let cuboid = step.cuboid();
+
+        self.cuboids = self
+            .cuboids
+            .iter()
+            .flat_map(|c| c.subtract(&cuboid))
+            .collect();
+        if step.on {
+            self.cuboids.push(cuboid);
+        }
+
+        self.steps_applied += 1;
+    }
+
+    pub fn steps_applied(&self) -> usize {
+        self.steps_applied
+    }
+
+    pub fn is_on(&self, point: (i64, i64, i64)) -> bool {
+        self.cuboids.iter().any(|c| c.contains(point))
+    }
+
+    /// Counted in `i128`: each volume fits in an `i64`, but their sum may not.
+    pub fn count_on(&self) -> i128 {
+        self.cuboids.iter().map(|c| c.volume() as i128).sum()
+    }
+
+    pub fn count_on_in(&self, region: &Cuboid) -> i128 {
+        self.cuboids
+            .iter()
+            .filter_map(|c| c.intersection(region))
+            .map(|c| c.volume() as i128)
+            .sum()
+    }
+
+    /// Disjoint cuboids covering exactly the cubes that are on.
+    pub fn cuboids(&self) -> &[Cuboid] {
+        &self.cuboids
+    }
+}
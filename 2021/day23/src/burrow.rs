@@ -0,0 +1,526 @@
+use std::{fmt::Write, str::FromStr};
+
+use crate::{Amphipod, Error, Field, Field::*};
+
+/// Hallway of any length with rooms of equal depth below it. Room `i` is the
+/// destination of amphipod `i`, and `rooms[i][0]` is the field next to the
+/// hallway.
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct Burrow {
+    pub hallway: Vec<Field>,
+    pub rooms: Vec<Vec<Field>>,
+    pub room_positions: Vec<usize>,
+}
+
+/// A field of the burrow: a hallway position, or a slot of a room counted from
+/// the hallway.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Location {
+    Hallway(usize),
+    Room(usize, usize),
+}
+
+/// An amphipod moving from one field to another in a single step.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct Move {
+    pub from: Location,
+    pub to: Location,
+}
+
+impl Burrow {
+    pub fn depth(&self) -> usize {
+        self.rooms[0].len()
+    }
+
+    fn is_entrance(&self, pos: usize) -> bool {
+        self.room_positions.contains(&pos)
+    }
+
+    /// Whether the room only holds amphipods that belong in it.
+    fn settled(&self, room_idx: usize) -> bool {
+        self.rooms[room_idx]
+            .iter()
+            .all(|f| f.amphiopod().is_none_or(|a| a.room() == room_idx))
+    }
+
+    pub fn completed(&self) -> bool {
+        self.rooms.iter().enumerate().all(|(room_idx, room)| {
+            room.iter()
+                .all(|f| f.amphiopod().is_some_and(|a| a.room() == room_idx))
+        })
+    }
+
+    /// Whether the hallway between `from` and `to` is free, not looking at
+    /// `from` itself.
+    fn hallway_clear(&self, from: usize, to: usize) -> bool {
+        let path = match from < to {
+            true => from + 1..to + 1,
+            false => to..from,
+        };
+        self.hallway[path].iter().all(|f| *f == Empty)
+    }
+
+    pub fn allowed_moves(&self) -> Vec<(Move, Burrow, usize)> {
+        let mut allowed_moves = vec![];
+
+        // from rooms
+        for room_idx in 0..self.rooms.len() {
+            if self.settled(room_idx) {
+                continue;
+            }
+
+            let (slot, amphipod) = match self.rooms[room_idx]
+                .iter()
+                .enumerate()
+                .find_map(|(slot, f)| f.amphiopod().map(|a| (slot, a)))
+            {
+                Some(x) => x,
+                None => continue,
+            };
+
+            let entrance = self.room_positions[room_idx];
+            let left = (0..entrance).rev();
+            let right = entrance + 1..self.hallway.len();
+
+            for path in [left.collect::<Vec<_>>(), right.collect()] {
+                for pos in path {
+                    if self.hallway[pos] != Empty {
+                        break;
+                    }
+                    if self.is_entrance(pos) {
+                        continue;
+                    }
+
+                    let mut burrow = self.clone();
+                    burrow.rooms[room_idx][slot] = Empty;
+                    burrow.hallway[pos] = Taken(amphipod);
+
+                    let mv = Move {
+                        from: Location::Room(room_idx, slot),
+                        to: Location::Hallway(pos),
+                    };
+                    let moves = slot + 1 + entrance.abs_diff(pos);
+                    allowed_moves.push((mv, burrow, moves * amphipod.energy()));
+                }
+            }
+        }
+
+        // from hallway
+        for (pos, field) in self.hallway.iter().enumerate() {
+            let amphipod = match field {
+                Taken(a) => *a,
+                Empty => continue,
+            };
+
+            let room_idx = amphipod.room();
+            if !self.settled(room_idx) {
+                continue;
+            }
+
+            let slot = match self.rooms[room_idx].iter().rposition(|f| *f == Empty) {
+                Some(slot) => slot,
+                None => continue,
+            };
+
+            let entrance = self.room_positions[room_idx];
+            if !self.hallway_clear(pos, entrance) {
+                continue;
+            }
+
+            let mut burrow = self.clone();
+            burrow.hallway[pos] = Empty;
+            burrow.rooms[room_idx][slot] = Taken(amphipod);
+
+            let mv = Move {
+                from: Location::Hallway(pos),
+                to: Location::Room(room_idx, slot),
+            };
+            let moves = pos.abs_diff(entrance) + slot + 1;
+            allowed_moves.push((mv, burrow, moves * amphipod.energy()));
+        }
+
+        allowed_moves
+    }
+
+    /// Applies `mv` if it is one of the allowed moves, returning the new
+    /// burrow and the energy it costs.
+    pub fn apply(&self, mv: &Move) -> Option<(Burrow, usize)> {
+        self.allowed_moves()
+            .into_iter()
+            .find(|(m, _, _)| m == mv)
+            .map(|(_, burrow, energy)| (burrow, energy))
+    }
+
+    /// Bits needed to store one field: empty, or any of the amphipods.
+    fn field_bits(&self) -> u32 {
+        u32::BITS - (self.rooms.len() as u32).leading_zeros()
+    }
+
+    fn fields(&self) -> impl DoubleEndedIterator<Item = &Field> {
+        self.hallway.iter().chain(self.rooms.iter().flatten())
+    }
+
+    /// Packs the fields, hallway first and then room by room, into a single
+    /// number. The shape of the burrow is not stored, so it can only be
+    /// decoded by a burrow with the same shape.
+    pub fn encode(&self) -> u128 {
+        let bits = self.field_bits();
+        self.fields().rev().fold(0, |key, field| {
+            let code = field.amphiopod().map_or(0, |a| a.0 as u128 + 1);
+            key << bits | code
+        })
+    }
+
+    /// Fills a copy of this burrow with the fields packed in `key`.
+    pub fn decode(&self, mut key: u128) -> Burrow {
+        let bits = self.field_bits();
+        let mask = (1 << bits) - 1;
+        let mut burrow = self.clone();
+
+        let fields = burrow
+            .hallway
+            .iter_mut()
+            .chain(burrow.rooms.iter_mut().flatten());
+        for field in fields {
+            *field = match key & mask {
+                0 => Empty,
+                code => Taken(Amphipod(code as u8 - 1)),
+            };
+            key >>= bits;
+        }
+
+        burrow
+    }
+
+    /// A lower bound on the energy still needed: every amphipod that has to
+    /// move walks straight to its room as if nothing was in the way, and the
+    /// ones entering a room fill it from the top down.
+    pub fn min_energy_home(&self) -> usize {
+        let mut energy = 0;
+        let mut entering = vec![0usize; self.rooms.len()];
+
+        for (pos, field) in self.hallway.iter().enumerate() {
+            if let Taken(a) = field {
+                let steps = pos.abs_diff(self.room_positions[a.room()]) + 1;
+                energy += steps * a.energy();
+                entering[a.room()] += 1;
+            }
+        }
+
+        for (room_idx, room) in self.rooms.iter().enumerate() {
+            // Amphipods in their own room only leave if someone below them
+            // does not belong there.
+            let stay_below = room
+                .iter()
+                .rposition(|f| f.amphiopod().is_some_and(|a| a.room() != room_idx))
+                .map_or(0, |slot| slot + 1);
+
+            for (slot, field) in room.iter().enumerate().take(stay_below) {
+                if let Taken(a) = field {
+                    let from = self.room_positions[room_idx];
+                    let to = self.room_positions[a.room()];
+                    // Leaving and re-entering the same room needs a step aside
+                    // and back.
+                    let across = from.abs_diff(to).max(2);
+                    energy += (slot + 1 + across + 1) * a.energy();
+                    entering[a.room()] += 1;
+                }
+            }
+        }
+
+        for (room_idx, count) in entering.into_iter().enumerate() {
+            let energy_per_step = Amphipod(room_idx as u8).energy();
+            energy += count * count.saturating_sub(1) / 2 * energy_per_step;
+        }
+
+        energy
+    }
+}
+
+/// Hallway positions are written as their index from the left, room slots as
+/// the room's amphipod letter followed by the slot, e.g. `C0` for the top of
+/// the third room.
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::Hallway(pos) => write!(f, "{}", pos),
+            Location::Room(room, slot) => write!(f, "{}{}", Amphipod(*room as u8).to_char(), slot),
+        }
+    }
+}
+
+impl FromStr for Location {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidMove(s.to_owned());
+        let s = s.trim();
+
+        match s.chars().next() {
+            Some(c @ 'A'..='Z') => {
+                let room = Amphipod::from_char(c).ok_or_else(invalid)?.room();
+                let slot = s[1..].parse().map_err(|_| invalid())?;
+                Ok(Location::Room(room, slot))
+            }
+            _ => s.parse().map(Location::Hallway).map_err(|_| invalid()),
+        }
+    }
+}
+
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> {}", self.from, self.to)
+    }
+}
+
+/// Parses `<from> -> <to>`.
+impl FromStr for Move {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (from, to) = s
+            .split_once("->")
+            .ok_or_else(|| Error::InvalidMove(s.to_owned()))?;
+
+        Ok(Move {
+            from: from.parse()?,
+            to: to.parse()?,
+        })
+    }
+}
+
+fn parse_field(c: char, line: usize, column: usize) -> Result<Field, Error> {
+    match c {
+        '.' => Ok(Empty),
+        c => Amphipod::from_char(c)
+            .map(Taken)
+            .ok_or(Error::InvalidField {
+                line: line + 1,
+                column: column + 1,
+                found: c,
+            }),
+    }
+}
+
+/// Parses the puzzle's ASCII diagram. The room columns are taken from the
+/// first row below the hallway, and every following row with fields adds one
+/// level of depth.
+impl FromStr for Burrow {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<Vec<char>> = s.lines().map(|l| l.chars().collect()).collect();
+        let invalid = |msg: &str| Error::InvalidDiagram(msg.to_owned());
+
+        let hallway_line = lines.get(1).ok_or_else(|| invalid("missing hallway"))?;
+        let hallway_len = hallway_line
+            .iter()
+            .skip(1)
+            .take_while(|c| **c != '#')
+            .count();
+        if hallway_len == 0 {
+            return Err(invalid("missing hallway"));
+        }
+        let hallway = hallway_line[1..hallway_len + 1]
+            .iter()
+            .enumerate()
+            .map(|(column, c)| parse_field(*c, 1, column + 1))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let first_room_line = lines.get(2).ok_or_else(|| invalid("missing rooms"))?;
+        let room_columns: Vec<usize> = first_room_line
+            .iter()
+            .enumerate()
+            .filter(|(column, c)| (1..=hallway_len).contains(column) && !matches!(c, '#' | ' '))
+            .map(|(column, _)| column)
+            .collect();
+        if room_columns.is_empty() {
+            return Err(invalid("no rooms"));
+        }
+
+        let mut rooms = vec![vec![]; room_columns.len()];
+        for (line_no, line) in lines.iter().enumerate().skip(2) {
+            if line.iter().all(|c| matches!(c, '#' | ' ')) {
+                break;
+            }
+
+            for (room, column) in rooms.iter_mut().zip(room_columns.iter()) {
+                let c = line.get(*column).copied().unwrap_or(' ');
+                room.push(parse_field(c, line_no, *column)?);
+            }
+        }
+
+        let burrow = Self {
+            hallway,
+            rooms,
+            room_positions: room_columns.iter().map(|c| c - 1).collect(),
+        };
+
+        let mut counts = vec![0; burrow.rooms.len()];
+        for amphipod in burrow.fields().filter_map(|f| f.amphiopod()) {
+            match counts.get_mut(amphipod.room()) {
+                Some(count) => *count += 1,
+                None => return Err(invalid(&format!("no room for {}", amphipod.to_char()))),
+            }
+        }
+        if counts.iter().any(|c| *c != burrow.depth()) {
+            return Err(invalid("every room needs as many amphipods as it is deep"));
+        }
+        if burrow.fields().count() * burrow.field_bits() as usize > u128::BITS as usize {
+            return Err(invalid("too many fields to encode"));
+        }
+
+        Ok(burrow)
+    }
+}
+
+impl std::fmt::Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Empty => f.write_char('.'),
+            Taken(a) => f.write_char(a.to_char()),
+        }
+    }
+}
+
+impl std::fmt::Display for Burrow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self.hallway.len() + 2;
+        let first = self.room_positions[0] + 1;
+        let last = self.room_positions[self.room_positions.len() - 1] + 1;
+
+        writeln!(f, "{}", "#".repeat(width))?;
+        f.write_char('#')?;
+        for field in self.hallway.iter() {
+            field.fmt(f)?;
+        }
+        f.write_str("#\n")?;
+
+        for level in 0..self.depth() {
+            let columns = match level {
+                0 => 0..width,
+                _ => 0..last + 2,
+            };
+
+            for column in columns {
+                match self.room_positions.iter().position(|p| p + 1 == column) {
+                    Some(room) => self.rooms[room][level].fmt(f)?,
+                    None if level > 0 && column + 1 < first => f.write_char(' ')?,
+                    None => f.write_char('#')?,
+                }
+            }
+            f.write_char('\n')?;
+        }
+
+        writeln!(
+            f,
+            "{}{}",
+            " ".repeat(first - 1),
+            "#".repeat(last - first + 3)
+        )
+    }
+}
+
+#[test]
+fn test_parse_and_display() {
+    let diagram = "#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########
+";
+    let burrow: Burrow = diagram.parse().unwrap();
+
+    assert_eq!(burrow.hallway.len(), 11);
+    assert_eq!(burrow.room_positions, vec![2, 4, 6, 8]);
+    assert_eq!(burrow.depth(), 2);
+    assert_eq!(
+        burrow.rooms[1],
+        vec![Taken(Amphipod(2)), Taken(Amphipod(3))]
+    );
+    assert_eq!(burrow.to_string(), diagram);
+
+    assert!(matches!(
+        "#####\n#...#\n#A#x#\n#####".parse::<Burrow>(),
+        Err(Error::InvalidField {
+            line: 3,
+            column: 4,
+            found: 'x'
+        })
+    ));
+    assert!(matches!(
+        "#####\n#...#\n#A#C#\n#####".parse::<Burrow>(),
+        Err(Error::InvalidDiagram(_))
+    ));
+    assert!(matches!(
+        "#####\n#...#\n#A#A#\n#####".parse::<Burrow>(),
+        Err(Error::InvalidDiagram(_))
+    ));
+    for diagram in ["#####\n\n#A#\n", "#####\n#\n#A#\n", "#####"] {
+        match diagram.parse::<Burrow>() {
+            Err(Error::InvalidDiagram(msg)) => assert_eq!(msg, "missing hallway"),
+            other => panic!("{:?} parsed as {:?}", diagram, other),
+        }
+    }
+}
+
+#[test]
+fn test_moves() {
+    let burrow: Burrow = "#####\n#...#\n#B#A#\n#####".parse().unwrap();
+    let mv: Move = "A0 -> 1".parse().unwrap();
+
+    assert_eq!(
+        mv,
+        Move {
+            from: Location::Room(0, 0),
+            to: Location::Hallway(1)
+        }
+    );
+    assert_eq!(mv.to_string(), "A0 -> 1");
+    assert_eq!(burrow.apply(&mv).map(|(_, energy)| energy), Some(20));
+    // Amphipods never stop right outside a room.
+    assert_eq!(burrow.apply(&"A0 -> 0".parse().unwrap()), None);
+    assert!(matches!("A0 0".parse::<Move>(), Err(Error::InvalidMove(_))));
+    assert!(matches!(
+        "a0 -> 0".parse::<Move>(),
+        Err(Error::InvalidMove(_))
+    ));
+}
+
+#[test]
+fn test_encoding() {
+    let burrow: Burrow = "#############
+#.A.......D.#
+###B#C#.#.###
+  #A#D#C#B#
+  #########"
+        .parse()
+        .unwrap();
+
+    assert_eq!(burrow.field_bits(), 3);
+    assert_eq!(burrow.encode() & 0b111_111, 0b001_000);
+    assert_eq!(burrow.decode(burrow.encode()), burrow);
+
+    let (next, _) = burrow.apply(&"A0 -> 3".parse().unwrap()).unwrap();
+    assert_ne!(next.encode(), burrow.encode());
+    assert_eq!(burrow.decode(next.encode()), next);
+}
+
+#[test]
+fn test_min_energy_home() {
+    let done: Burrow = "#####\n#...#\n#A#B#\n#####".parse().unwrap();
+    assert_eq!(done.min_energy_home(), 0);
+
+    // Both cross straight over, while the best solution takes 46.
+    let burrow: Burrow = "###########\n#.........#\n###B#A#####\n  #####"
+        .parse()
+        .unwrap();
+    assert_eq!(burrow.min_energy_home(), 4 * 10 + 4);
+
+    // Both As have to make way for the Bs below them, and they fill their
+    // room one slot deeper than the other.
+    let burrow: Burrow = "#######\n#.....#\n###A#A#\n  #B#B#\n  #####"
+        .parse()
+        .unwrap();
+    assert_eq!(burrow.min_energy_home(), 4 + 5 * 10 + 4 + 1);
+}
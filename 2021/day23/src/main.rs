@@ -1,29 +1,77 @@
 use std::collections::BinaryHeap;
-use std::collections::HashSet;
+use std::collections::HashMap;
+
+mod burrow;
+
+use burrow::{Burrow, Move};
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    InvalidDiagram(String),
+    InvalidField {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    InvalidMove(String),
+    IllegalMove {
+        line: usize,
+        mv: Move,
+    },
+    InvalidOption(String),
+}
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
-enum Amphipod {
-    Amber,
-    Bronze,
-    Copper,
-    Desert,
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {}", e),
+            Self::InvalidDiagram(s) => write!(f, "invalid diagram: {}", s),
+            Self::InvalidField {
+                line,
+                column,
+                found,
+            } => write!(
+                f,
+                "line {}, column {}: invalid field {:?}",
+                line, column, found
+            ),
+            Self::InvalidMove(s) => write!(f, "cannot parse move {:?}", s),
+            Self::IllegalMove { line, mv } => write!(f, "line {}: illegal move {}", line, mv),
+            Self::InvalidOption(s) => write!(f, "invalid option: {}", s),
+        }
+    }
 }
 
+impl std::error::Error for Error {}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct Amphipod(u8);
+
 impl Amphipod {
     fn energy(&self) -> usize {
-        match self {
-            Self::Amber => 1,
-            Self::Bronze => 10,
-            Self::Copper => 100,
-            Self::Desert => 1000,
+        10usize.pow(self.0 as u32)
+    }
+
+    /// Index of the destination room.
+    fn room(&self) -> usize {
+        self.0 as usize
+    }
+
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'A'..='Z' => Some(Self(c as u8 - b'A')),
+            _ => None,
         }
     }
-}
 
-use self::Amphipod::*;
+    fn to_char(self) -> char {
+        (b'A' + self.0) as char
+    }
+}
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
-enum Field {
+pub enum Field {
     Empty,
     Taken(Amphipod),
 }
@@ -33,248 +81,309 @@ use self::Field::*;
 impl Field {
     fn amphiopod(&self) -> Option<Amphipod> {
         match &self {
-            Taken(a) => Some(a.clone()),
+            Taken(a) => Some(*a),
             Empty => None,
         }
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
-struct Burrow {
-    rooms: [(Field, Field, Field, Field); 4],
-    hallway: [Field; 7],
-}
-
-impl Burrow {
-    fn new(rooms: [(Field, Field, Field, Field); 4]) -> Self {
-        Self {
-            hallway: [Field::Empty; 7],
-            rooms: rooms,
-        }
-    }
-
-    fn completed(&self) -> bool {
-        match self.rooms {
-            [(Taken(Amber), Taken(Amber), Taken(Amber), Taken(Amber)), (Taken(Bronze), Taken(Bronze), Taken(Bronze), Taken(Bronze)), (Taken(Copper), Taken(Copper), Taken(Copper), Taken(Copper)), (Taken(Desert), Taken(Desert), Taken(Desert), Taken(Desert))] => {
-                true
-            }
-            _ => false,
-        }
-    }
-
-    fn allowed_moves(&self) -> Vec<(Burrow, usize)> {
-        let mut allowed_moves = vec![];
-
-        // from rooms
-        for room_idx in 0..4 {
-            let mut burrow = self.clone();
-            let amphipod = match &mut burrow.rooms[room_idx] {
-                (Empty, Empty, Empty, Empty) => None,
-                (Empty, Empty, Empty, field) => Some((field, 4)),
-                (Empty, Empty, field, _) => Some((field, 3)),
-                (Empty, field, _, _) => Some((field, 2)),
-                (field, _, _, _) => Some((field, 1)),
-            };
-            if let Some((field, moves)) = amphipod {
-                let amphipod = field.amphiopod().unwrap();
-                let new_field = field.clone();
-                *field = Empty;
-
-                // left
-                for i in 0..2 + room_idx {
-                    let pos = 1 + room_idx - i;
-                    if let Taken(_) = self.hallway[pos] {
-                        break;
-                    }
-
-                    let mut burrow = burrow.clone();
-                    burrow.hallway[pos] = new_field;
-
-                    let mut moves = moves + 1 + (i * 2);
-                    if pos == 0 {
-                        moves -= 1;
-                    }
-
-                    allowed_moves.push((burrow, moves * amphipod.energy()));
-                }
-                // right
-                for i in 0..5 - room_idx {
-                    let pos = 2 + i + room_idx;
-                    if let Taken(_) = self.hallway[pos] {
-                        break;
-                    }
-
-                    let mut a_burrow = burrow.clone();
-                    a_burrow.hallway[pos] = new_field;
-
-                    let mut moves = moves + 1 + (i * 2);
-                    if pos == 6 {
-                        moves -= 1;
-                    }
-
-                    allowed_moves.push((a_burrow, moves * amphipod.energy()));
-                }
-            }
-        }
-
-        for hallway_idx in 0..7 {
-            let mut burrow = self.clone();
-            let from_field = &mut burrow.hallway[hallway_idx];
-
-            let target_room: Option<usize> = match from_field.clone() {
-                Empty => None,
-                Taken(Amber) => Some(0),
-                Taken(Bronze) => Some(1),
-                Taken(Copper) => Some(2),
-                Taken(Desert) => Some(3),
-            };
-            if target_room.is_none() {
-                continue;
-            }
-            let amphipod = from_field.amphiopod().unwrap();
-
-            let mut moves = 0;
-
-            let target_room = target_room.unwrap();
-            let dest_field = match &mut burrow.rooms[target_room] {
-                (Taken(_), _, _, _) => continue,
-                (field @ Empty, Taken(a), Taken(b), Taken(c)) => {
-                    if amphipod != *a || amphipod != *b || amphipod != *c {
-                        continue;
-                    }
-
-                    moves += 1;
-                    field
-                }
-                (Empty, field @ Empty, Taken(a), Taken(b)) => {
-                    if amphipod != *a || amphipod != *b {
-                        continue;
-                    }
-
-                    moves += 2;
-                    field
-                }
-                (Empty, Empty, field @ Empty, Taken(a)) => {
-                    if amphipod != *a {
-                        continue;
-                    }
-
-                    moves += 3;
-                    field
-                }
-
-                (Empty, Empty, Empty, field @ Empty) => {
-                    moves += 4;
-                    field
-                }
-                _ => continue,
-            };
-
-            let amphipod = from_field.amphiopod().unwrap();
-            *from_field = Empty;
-
-            let mut hallway_idx = hallway_idx;
-
-            match (burrow.hallway[1], burrow.hallway[5], hallway_idx) {
-                (Taken(_), _, 0) => continue,
-                (_, Taken(_), 6) => continue,
-                (Empty, _, 0) => {
-                    moves += 1;
-                    hallway_idx = 1;
-                }
-                (_, Empty, 6) => {
-                    moves += 1;
-                    hallway_idx = 5;
-                }
-                _ => {}
-            }
-
-            let move_dir = if (target_room as f32 + 1.5) - hallway_idx as f32 >= 0.0 {
-                1
-            } else {
-                -1
-            };
-
-            let checks: Vec<usize> = match move_dir {
-                1 => ((hallway_idx + 1)..(target_room + 2)).collect(),
-                -1 => ((target_room + 2)..(hallway_idx)).collect(),
-                _ => unreachable!(),
-            };
-
-            let mut skip = false;
-            for check in checks.iter() {
-                if let Taken(_) = burrow.hallway[*check] {
-                    skip = true;
-                    break;
-                }
-            }
-            if skip {
-                continue;
-            };
-
-            moves += 1 + checks.len() * 2;
-            *dest_field = Taken(amphipod);
-            allowed_moves.push((burrow, moves * amphipod.energy()));
-        }
+/// Rows inserted into the diagram after the first room row when it is
+/// unfolded for part 2.
+const UNFOLDED_ROWS: [&str; 2] = ["  #D#C#B#A#", "  #D#B#A#C#"];
 
-        allowed_moves
-    }
+/// Inserts the folded rows of the diagram below its first room row.
+fn unfold(diagram: &str) -> String {
+    let mut lines: Vec<&str> = diagram.lines().collect();
+    let at = 3.min(lines.len());
+    lines.splice(at..at, UNFOLDED_ROWS);
+    lines.join("\n")
 }
 
 #[derive(PartialEq, Eq)]
 struct BurrowState {
-    burrow: Burrow,
+    key: u128,
     energy: usize,
+    /// Energy spent so far plus the estimate of what is still needed.
+    estimate: usize,
 }
 
+/// Reversed, so that the heap pops the lowest estimate first. Energy and key
+/// only break ties, so that the order agrees with `Eq`.
 impl Ord for BurrowState {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.energy.cmp(&self.energy)
+        (other.estimate, other.energy, other.key).cmp(&(self.estimate, self.energy, self.key))
     }
 }
 
 impl PartialOrd for BurrowState {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        other.energy.partial_cmp(&self.energy)
+        Some(self.cmp(other))
+    }
+}
+
+/// A sequence of moves with the burrow after each one and what it cost.
+#[derive(Debug, PartialEq, Eq)]
+struct Solution {
+    start: Burrow,
+    steps: Vec<(Move, Burrow, usize)>,
+}
+
+impl Solution {
+    fn energy(&self) -> usize {
+        self.steps.iter().map(|(_, _, energy)| energy).sum()
+    }
+
+    fn end(&self) -> &Burrow {
+        self.steps
+            .last()
+            .map(|(_, burrow, _)| burrow)
+            .unwrap_or(&self.start)
+    }
+
+    fn print(&self) {
+        print!("{}", self.start);
+        for (i, (mv, burrow, energy)) in self.steps.iter().enumerate() {
+            println!("\nMove {}: {} (energy {})", i + 1, mv, energy);
+            print!("{}", burrow);
+        }
     }
 }
 
-fn main() {
-    let burrow = Burrow::new([
-        (Taken(Copper), Taken(Desert), Taken(Desert), Taken(Desert)),
-        (Taken(Copper), Taken(Copper), Taken(Bronze), Taken(Amber)),
-        (Taken(Bronze), Taken(Bronze), Taken(Amber), Taken(Bronze)),
-        (Taken(Desert), Taken(Amber), Taken(Copper), Taken(Amber)),
-    ]);
+struct Search {
+    solution: Option<Solution>,
+    expanded: usize,
+}
+
+/// Finds the moves organizing the amphipods with the minimum energy using A*
+/// over packed burrows, or plain Dijkstra without the heuristic.
+fn solve(burrow: Burrow, heuristic: bool) -> Search {
+    let estimate = |burrow: &Burrow| match heuristic {
+        true => burrow.min_energy_home(),
+        false => 0,
+    };
 
-    let mut visited: HashSet<Burrow> = HashSet::new();
+    // Cheapest known energy to reach a burrow, and the burrow and move it was
+    // reached with.
+    let mut best: HashMap<u128, usize> = HashMap::new();
+    let mut came_from: HashMap<u128, (u128, Move)> = HashMap::new();
+    let mut expanded = 0;
+
+    let start = burrow.encode();
+    best.insert(start, 0);
 
     let mut burrows = BinaryHeap::new();
     burrows.push(BurrowState {
-        burrow: burrow,
+        key: start,
         energy: 0,
+        estimate: estimate(&burrow),
     });
 
     while let Some(burrow_state) = burrows.pop() {
-        if visited.contains(&burrow_state.burrow) {
+        // A cheaper way here was found after this one was queued.
+        if best[&burrow_state.key] < burrow_state.energy {
             continue;
         }
-        visited.insert(burrow_state.burrow);
+        expanded += 1;
+
+        let current = burrow.decode(burrow_state.key);
+        if current.completed() {
+            let mut moves = vec![];
+            let mut key = burrow_state.key;
+            while let Some((from, mv)) = came_from.get(&key) {
+                moves.push((0, *mv));
+                key = *from;
+            }
+            moves.reverse();
 
-        if burrow_state.burrow.completed() {
-            println!("Complated: {}", burrow_state.energy);
-            break;
+            return Search {
+                solution: replay(burrow, moves).ok(),
+                expanded,
+            };
         }
 
-        let moves = burrow_state.burrow.allowed_moves();
+        for (mv, next, new_energy) in current.allowed_moves() {
+            let key = next.encode();
+            let energy = burrow_state.energy + new_energy;
+            if best.get(&key).is_some_and(|e| *e <= energy) {
+                continue;
+            }
 
-        for (burrow, new_energy) in moves {
+            best.insert(key, energy);
+            came_from.insert(key, (burrow_state.key, mv));
             burrows.push(BurrowState {
-                burrow: burrow,
-                energy: burrow_state.energy + new_energy,
+                key,
+                energy,
+                estimate: energy + estimate(&next),
             });
         }
     }
 
-    println!("{}", burrows.len())
+    Search {
+        solution: None,
+        expanded,
+    }
+}
+
+/// Applies the moves, given with the line they were read from, checking
+/// that each one is allowed.
+fn replay<I>(burrow: Burrow, moves: I) -> Result<Solution, Error>
+where
+    I: IntoIterator<Item = (usize, Move)>,
+{
+    let mut solution = Solution {
+        start: burrow,
+        steps: vec![],
+    };
+
+    for (line, mv) in moves {
+        let (burrow, energy) = solution
+            .end()
+            .apply(&mv)
+            .ok_or(Error::IllegalMove { line, mv })?;
+        solution.steps.push((mv, burrow, energy));
+    }
+
+    Ok(solution)
+}
+
+/// Parses one move per line, skipping blank lines.
+fn parse_moves(s: &str) -> Result<Vec<(usize, Move)>, Error> {
+    s.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| Ok((i + 1, line.parse()?)))
+        .collect()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut input_file = "day23/input.txt".to_owned();
+    let mut replay_file = None;
+    let mut unfolded = false;
+    let mut heuristic = true;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--replay" => {
+                let file = args
+                    .next()
+                    .ok_or_else(|| Error::InvalidOption(format!("{} requires a value", arg)))?;
+                replay_file = Some(file);
+            }
+            "--unfold" => unfolded = true,
+            "--no-heuristic" => heuristic = false,
+            _ => input_file = arg,
+        }
+    }
+
+    let diagram = std::fs::read_to_string(input_file).map_err(Error::Io)?;
+
+    if let Some(replay_file) = replay_file {
+        let diagram = match unfolded {
+            true => unfold(&diagram),
+            false => diagram,
+        };
+        let moves = parse_moves(&std::fs::read_to_string(replay_file).map_err(Error::Io)?)?;
+
+        let solution = replay(diagram.parse()?, moves)?;
+        solution.print();
+        println!(
+            "\nEnergy: {}, completed: {}",
+            solution.energy(),
+            solution.end().completed()
+        );
+        return Ok(());
+    }
+
+    for (part, diagram) in [(1, diagram.clone()), (2, unfold(&diagram))] {
+        let burrow: Burrow = diagram.parse()?;
+        let search = solve(burrow, heuristic);
+        match search.solution {
+            Some(solution) => {
+                solution.print();
+                println!("\nPart{}: {}", part, solution.energy());
+            }
+            None => println!("Part{}: no solution", part),
+        }
+        println!("States expanded: {}\n", search.expanded);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########";
+
+#[test]
+fn test_example() {
+    for heuristic in [true, false] {
+        let solution = solve(EXAMPLE.parse().unwrap(), heuristic).solution.unwrap();
+        assert_eq!(solution.energy(), 12521);
+        assert!(solution.end().completed());
+    }
+
+    let search = solve(unfold(EXAMPLE).parse().unwrap(), true);
+    assert_eq!(search.solution.unwrap().energy(), 44169);
+    assert!(search.expanded < solve(unfold(EXAMPLE).parse().unwrap(), false).expanded);
+}
+
+#[test]
+fn test_other_shapes() {
+    // Two rooms, one deep, with a longer hallway on the right.
+    let burrow: Burrow = "###########\n#.........#\n###B#A#####\n  #####"
+        .parse()
+        .unwrap();
+    assert_eq!(solve(burrow, true).solution.map(|s| s.energy()), Some(46));
+
+    // The only free space in the hallway is between the two rooms.
+    let burrow: Burrow = "#####\n#...#\n#B#A#\n#####".parse().unwrap();
+    assert!(solve(burrow, true).solution.is_none());
+
+    // Three rooms, three deep.
+    let burrow: Burrow = "#########\n#.......#\n###A#C#B#\n  #B#A#C#\n  #A#B#C#\n  #######"
+        .parse()
+        .unwrap();
+    assert_eq!(burrow.depth(), 3);
+    assert!(solve(burrow, true).solution.is_some());
+}
+
+#[test]
+fn test_replay() {
+    let moves = "C0 -> 3
+
+B0 -> 5
+";
+    let moves = parse_moves(moves).unwrap();
+    assert_eq!(moves[1].0, 3);
+
+    let solution = replay(EXAMPLE.parse().unwrap(), moves).unwrap();
+    assert_eq!(solution.energy(), 40 + 200);
+    assert!(!solution.end().completed());
+
+    // The hallway to the left of the room is already taken.
+    let moves = parse_moves("C0 -> 3\nB0 -> 3").unwrap();
+    assert!(matches!(
+        replay(EXAMPLE.parse().unwrap(), moves),
+        Err(Error::IllegalMove { line: 2, .. })
+    ));
+}
+
+#[test]
+fn test_state_order() {
+    use std::cmp::Ordering;
+
+    let state = |key, energy, estimate| BurrowState {
+        key,
+        energy,
+        estimate,
+    };
+
+    assert_eq!(state(1, 5, 10).cmp(&state(2, 0, 20)), Ordering::Greater);
+    assert_eq!(state(1, 5, 10).cmp(&state(1, 6, 10)), Ordering::Greater);
+    assert_ne!(state(1, 5, 10).cmp(&state(2, 5, 10)), Ordering::Equal);
+    assert_eq!(state(1, 5, 10).cmp(&state(1, 5, 10)), Ordering::Equal);
 }
@@ -0,0 +1,334 @@
+use crate::{Error, Instruction, Register, Value};
+
+/// Index of a register in the register array.
+type Reg = usize;
+
+/// Operation with its operands resolved. Operations that can fault keep the index
+/// of the instruction they came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Inp(Reg, usize),
+    Set(Reg, i64),
+    /// `a = b * mul + add`, with `b` another register.
+    Affine(Reg, Reg, i64, i64),
+    /// `a = (b * mul + add) * c`, with `c` not `a`.
+    AffineMul(Reg, Reg, i64, i64, Reg),
+    /// `a = b * mul + add`, then `z = z * a`.
+    MulAffine(Reg, Reg, Reg, i64, i64),
+    /// `a = (b * mul + add) * c`, then `z = z + a`.
+    AddAffineMul(Reg, Reg, Reg, i64, i64, Reg),
+    AddImm(Reg, i64),
+    AddReg(Reg, Reg),
+    MulImm(Reg, i64),
+    MulReg(Reg, Reg),
+    DivImm(Reg, Divisor, usize),
+    DivReg(Reg, Reg, usize),
+    ModImm(Reg, Divisor, usize),
+    /// `a = b % modulus + add`, where `b` may be `a`.
+    ModAdd(Reg, Reg, Divisor, i64, usize),
+    ModReg(Reg, Reg, usize),
+    EqlImm(Reg, i64),
+    EqlReg(Reg, Reg),
+    NeqImm(Reg, i64),
+    NeqReg(Reg, Reg),
+}
+
+/// A program turned into operations that index the registers directly and
+/// with literal and register operands told apart up front. Behaves exactly like `ALU::execute`.
+#[derive(Debug, Clone)]
+pub struct Compiled {
+    ops: Vec<Op>,
+}
+
+fn compile(index: usize, inst: &Instruction) -> Op {
+    use Value::{Ident, NumberLiteral as Lit};
+
+    match inst {
+        Instruction::Inp(a) => Op::Inp(a.index(), index),
+        Instruction::Mul(a, Lit(0)) => Op::Set(a.index(), 0),
+        Instruction::Add(a, Lit(v)) => Op::AddImm(a.index(), *v),
+        Instruction::Add(a, Ident(b)) => Op::AddReg(a.index(), b.index()),
+        Instruction::Mul(a, Lit(v)) => Op::MulImm(a.index(), *v),
+        Instruction::Mul(a, Ident(b)) => Op::MulReg(a.index(), b.index()),
+        Instruction::Div(a, Lit(v)) => Op::DivImm(a.index(), Divisor::new(*v), index),
+        Instruction::Div(a, Ident(b)) => Op::DivReg(a.index(), b.index(), index),
+        Instruction::Mod(a, Lit(v)) => Op::ModImm(a.index(), Divisor::new(*v), index),
+        Instruction::Mod(a, Ident(b)) => Op::ModReg(a.index(), b.index(), index),
+        Instruction::Eql(a, Lit(v)) => Op::EqlImm(a.index(), *v),
+        Instruction::Eql(a, Ident(b)) => Op::EqlReg(a.index(), b.index()),
+    }
+}
+
+/// Merges an operation into the previous one when the pair has the same
+/// effect as a single operation. Arithmetic wraps, so scaling and shifting
+/// an affine value stays affine.
+fn fuse(prev: Op, op: Op) -> Option<Op> {
+    match (prev, op) {
+        (Op::Set(a, v), Op::AddImm(b, w)) if a == b => Some(Op::Set(a, v.wrapping_add(w))),
+        (Op::Set(a, 0), Op::AddReg(b, c)) if a == b && a != c => Some(Op::Affine(a, c, 1, 0)),
+        (Op::Set(a, v), Op::MulReg(b, c)) if a == b && a != c => Some(Op::Affine(a, c, v, 0)),
+        (Op::Affine(a, b, m, k), Op::AddImm(c, w)) if a == c => {
+            Some(Op::Affine(a, b, m, k.wrapping_add(w)))
+        }
+        (Op::Affine(a, b, m, k), Op::MulImm(c, w)) if a == c => {
+            Some(Op::Affine(a, b, m.wrapping_mul(w), k.wrapping_mul(w)))
+        }
+        (Op::Affine(a, b, 1, 0), Op::ModImm(c, d, index)) if a == c => {
+            Some(Op::ModAdd(a, b, d, 0, index))
+        }
+        (Op::ModImm(a, d, index), Op::AddImm(b, w)) if a == b => {
+            Some(Op::ModAdd(a, a, d, w, index))
+        }
+        (Op::ModAdd(a, b, d, k, index), Op::AddImm(c, w)) if a == c => {
+            Some(Op::ModAdd(a, b, d, k.wrapping_add(w), index))
+        }
+        (Op::Affine(a, b, m, k), Op::MulReg(c, d)) if a == c && a != d => {
+            Some(Op::AffineMul(a, b, m, k, d))
+        }
+        (Op::Affine(a, b, m, k), Op::MulReg(z, c)) if a == c && z != a => {
+            Some(Op::MulAffine(z, a, b, m, k))
+        }
+        (Op::AffineMul(a, b, m, k, c), Op::AddReg(z, d)) if a == d && z != a => {
+            Some(Op::AddAffineMul(z, a, b, m, k, c))
+        }
+        (Op::EqlImm(a, v), Op::EqlImm(b, 0)) if a == b => Some(Op::NeqImm(a, v)),
+        (Op::EqlReg(a, c), Op::EqlImm(b, 0)) if a == b => Some(Op::NeqReg(a, c)),
+        _ => None,
+    }
+}
+
+impl Compiled {
+    pub fn new(instructions: &[Instruction]) -> Self {
+        let mut ops: Vec<Op> = Vec::with_capacity(instructions.len());
+
+        for (index, inst) in instructions.iter().enumerate() {
+            let op = compile(index, inst);
+            if matches!(
+                op,
+                Op::AddImm(_, 0) | Op::MulImm(_, 1) | Op::DivImm(_, Divisor { value: 1, .. }, _)
+            ) {
+                continue;
+            }
+
+            match ops.last().and_then(|prev| fuse(*prev, op)) {
+                Some(fused) => *ops.last_mut().unwrap() = fused,
+                None => ops.push(op),
+            }
+        }
+
+        Self { ops }
+    }
+
+    /// Runs the program on `registers`, in `Register::index` order.
+    pub fn execute(&self, r: &mut [i64; 4], input: &[i64]) -> Result<(), Error> {
+        let mut input_iter = input.iter();
+
+        for op in self.ops.iter() {
+            match *op {
+                Op::Inp(a, index) => {
+                    r[a] = *input_iter.next().ok_or(Error::InputExhausted { index })?
+                }
+                Op::Set(a, v) => r[a] = v,
+                Op::Affine(a, b, m, k) => r[a] = r[b].wrapping_mul(m).wrapping_add(k),
+                Op::AffineMul(a, b, m, k, c) => {
+                    r[a] = r[b].wrapping_mul(m).wrapping_add(k).wrapping_mul(r[c])
+                }
+                Op::MulAffine(z, a, b, m, k) => {
+                    r[a] = r[b].wrapping_mul(m).wrapping_add(k);
+                    r[z] = r[z].wrapping_mul(r[a]);
+                }
+                Op::AddAffineMul(z, a, b, m, k, c) => {
+                    r[a] = r[b].wrapping_mul(m).wrapping_add(k).wrapping_mul(r[c]);
+                    r[z] = r[z].wrapping_add(r[a]);
+                }
+                Op::AddImm(a, v) => r[a] = r[a].wrapping_add(v),
+                Op::AddReg(a, b) => r[a] = r[a].wrapping_add(r[b]),
+                Op::MulImm(a, v) => r[a] = r[a].wrapping_mul(v),
+                Op::MulReg(a, b) => r[a] = r[a].wrapping_mul(r[b]),
+                Op::DivImm(a, d, index) => r[a] = d.div(r[a], index)?,
+                Op::DivReg(a, b, index) => r[a] = div(r[a], r[b], index)?,
+                Op::ModImm(a, d, index) => r[a] = d.modulo(r[a], index)?,
+                Op::ModAdd(a, b, d, w, index) => {
+                    r[a] = r[b];
+                    r[a] = d.modulo(r[a], index)?.wrapping_add(w);
+                }
+                Op::ModReg(a, b, index) => r[a] = modulo(r[a], r[b], index)?,
+                Op::EqlImm(a, v) => r[a] = (r[a] == v) as i64,
+                Op::EqlReg(a, b) => r[a] = (r[a] == r[b]) as i64,
+                Op::NeqImm(a, v) => r[a] = (r[a] != v) as i64,
+                Op::NeqReg(a, b) => r[a] = (r[a] != r[b]) as i64,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs the program from zeroed registers and returns `z`.
+    pub fn run(&self, input: &[i64]) -> Result<i64, Error> {
+        let mut registers = [0; 4];
+        self.execute(&mut registers, input)?;
+        Ok(registers[Register::Z.index()])
+    }
+}
+
+/// A literal divisor with a multiplier precomputed, so that non-negative
+/// values are divided by multiplying instead of with a slow division
+/// instruction. This is where most of the time of a MONAD run used to go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Divisor {
+    value: i64,
+    /// `ceil(2^(63 + shift) / value)`, by which `n / value` is
+    /// `n * magic >> (63 + shift)` for every `0 <= n < 2^63`
+    /// (Granlund and Montgomery, theorem 4.2).
+    magic: u128,
+    shift: u32,
+}
+
+impl Divisor {
+    fn new(value: i64) -> Self {
+        let (magic, shift) = match value > 0 {
+            true => {
+                let d = value as u128;
+                let shift = u64::BITS - (value as u64 - 1).leading_zeros();
+                ((1u128 << (63 + shift)).div_ceil(d), shift)
+            }
+            false => (0, 0),
+        };
+
+        Self {
+            value,
+            magic,
+            shift,
+        }
+    }
+
+    fn quotient(&self, n: i64) -> i64 {
+        ((n as u128 * self.magic) >> (63 + self.shift)) as i64
+    }
+
+    fn div(&self, a: i64, index: usize) -> Result<i64, Error> {
+        match a >= 0 && self.value > 0 {
+            true => Ok(self.quotient(a)),
+            false => div(a, self.value, index),
+        }
+    }
+
+    fn modulo(&self, a: i64, index: usize) -> Result<i64, Error> {
+        match a >= 0 && self.value > 0 {
+            true => Ok(a - self.quotient(a) * self.value),
+            false => modulo(a, self.value, index),
+        }
+    }
+}
+
+fn div(a: i64, b: i64, index: usize) -> Result<i64, Error> {
+    match b {
+        0 => Err(Error::DivideByZero { index }),
+        _ => Ok(a.wrapping_div(b)),
+    }
+}
+
+fn modulo(a: i64, b: i64, index: usize) -> Result<i64, Error> {
+    match a < 0 || b <= 0 {
+        true => Err(Error::InvalidMod { index, a, b }),
+        false => Ok(a % b),
+    }
+}
+
+#[cfg(test)]
+use utils::random::XorShift;
+
+#[cfg(test)]
+fn random_program(next: &mut impl FnMut(i64) -> i64, len: usize) -> Vec<Instruction> {
+    let register = |n: i64| [Register::W, Register::X, Register::Y, Register::Z][n as usize];
+
+    (0..len)
+        .map(|_| {
+            let reg = register(next(4));
+            let val = match next(2) {
+                0 => Value::Ident(register(next(4))),
+                // Small literals, so that the fused patterns come up.
+                _ => Value::NumberLiteral(next(7) - 3),
+            };
+
+            match next(6) {
+                0 => Instruction::Inp(reg),
+                1 => Instruction::Add(reg, val),
+                2 => Instruction::Mul(reg, val),
+                3 => Instruction::Div(reg, val),
+                4 => Instruction::Mod(reg, val),
+                _ => Instruction::Eql(reg, val),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_matches_interpreter() {
+    // Seeded, so that failures can be reproduced.
+    let mut rng = XorShift::new(0x2545f4914f6cdd1d);
+    let mut next = |bound: i64| rng.below(bound as u64) as i64;
+
+    for _ in 0..2000 {
+        let len = next(40) as usize;
+        let program = random_program(&mut next, len);
+        let input: Vec<i64> = (0..next(8)).map(|_| next(19) - 9).collect();
+
+        let mut alu = crate::ALU::new();
+        let expected = alu.execute(&input, &program);
+
+        let mut registers = [0; 4];
+        let result = Compiled::new(&program).execute(&mut registers, &input);
+
+        assert_eq!(result, expected, "{:?} on {:?}", program, input);
+        assert_eq!(registers, alu.registers(), "{:?} on {:?}", program, input);
+    }
+}
+
+#[test]
+fn test_divisor() {
+    let mut rng = XorShift::new(0x9e3779b97f4a7c15);
+    let mut next = || rng.next_u64();
+
+    let mut divisors = vec![1, 2, 3, 7, 26, 1 << 20, (1 << 62) + 1, i64::MAX];
+    divisors.extend((0..200).map(|_| (next() >> (next() % 63 + 1)) as i64 + 1));
+
+    for value in divisors {
+        let d = Divisor::new(value);
+        let mut values = vec![0, 1, value - 1, value, i64::MAX - 1, i64::MAX];
+        values.extend((0..200).map(|_| (next() >> (next() % 64).max(1)) as i64));
+
+        for a in values.into_iter().filter(|a| *a >= 0) {
+            assert_eq!(d.div(a, 0), Ok(a / value), "{} / {}", a, value);
+            assert_eq!(d.modulo(a, 0), Ok(a % value), "{} % {}", a, value);
+        }
+    }
+
+    assert_eq!(Divisor::new(26).div(-27, 0), Ok(-1));
+    assert_eq!(
+        Divisor::new(0).div(5, 3),
+        Err(Error::DivideByZero { index: 3 })
+    );
+    assert!(Divisor::new(-26).modulo(5, 0).is_err());
+}
+
+#[test]
+fn test_fusing() {
+    let program = crate::load_instructions(&[
+        "mul x 0", "add x z", "mod x 26", "add x 11", "eql x w", "eql x 0", "mul y 0", "add y 25",
+        "mul y x", "add y 1", "mul z y", "mul y 0", "add y w", "add y 6", "mul y 2", "add z 0",
+        "div z 1", "mul y x", "add z y",
+    ])
+    .unwrap();
+    let [w, x, y, z] = [Register::W, Register::X, Register::Y, Register::Z].map(|r| r.index());
+
+    assert_eq!(
+        Compiled::new(&program).ops,
+        vec![
+            Op::ModAdd(x, z, Divisor::new(26), 11, 2),
+            Op::NeqReg(x, w),
+            Op::MulAffine(z, y, x, 25, 1),
+            Op::AddAffineMul(z, y, w, 2, 12, x),
+        ]
+    );
+}
@@ -4,12 +4,72 @@ use std::{
     str::FromStr,
 };
 
-#[derive(Debug)]
+mod compile;
+mod solver;
+mod symbolic;
+
+use compile::Compiled;
+use solver::Solver;
+
+#[derive(Debug, PartialEq, Eq)]
 enum Error {
-    ParseError,
+    Parse,
+    InvalidInstruction {
+        line: usize,
+        text: String,
+    },
+    InputExhausted {
+        index: usize,
+    },
+    DivideByZero {
+        index: usize,
+    },
+    InvalidMod {
+        index: usize,
+        a: i64,
+        b: i64,
+    },
+    InvalidOption(String),
+    /// The character at `position` of an input, counting from 1, is not a digit.
+    InvalidDigit {
+        position: usize,
+        found: char,
+    },
+    /// The solver found an input that the program does not accept.
+    Rejected {
+        input: String,
+        z: i64,
+    },
 }
 
-#[derive(Debug, PartialEq, Eq)]
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse => f.write_str("parse error"),
+            Self::InvalidInstruction { line, text } => {
+                write!(f, "line {}: invalid instruction {:?}", line, text)
+            }
+            Self::InputExhausted { index } => {
+                write!(f, "instruction {}: no input left", index)
+            }
+            Self::DivideByZero { index } => write!(f, "instruction {}: division by zero", index),
+            Self::InvalidMod { index, a, b } => {
+                write!(f, "instruction {}: invalid mod {} {}", index, a, b)
+            }
+            Self::InvalidOption(s) => write!(f, "invalid option: {}", s),
+            Self::InvalidDigit { position, found } => {
+                write!(f, "input position {}: {:?} is not a digit", position, found)
+            }
+            Self::Rejected { input, z } => {
+                write!(f, "{} was found but leaves z = {}", input, z)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Register {
     W,
     X,
@@ -26,11 +86,34 @@ impl FromStr for Register {
             "x" => Ok(Register::X),
             "y" => Ok(Register::Y),
             "z" => Ok(Register::Z),
-            _ => Err(Error::ParseError),
+            _ => Err(Error::Parse),
         }
     }
 }
 
+impl Register {
+    fn index(&self) -> usize {
+        match self {
+            Register::W => 0,
+            Register::X => 1,
+            Register::Y => 2,
+            Register::Z => 3,
+        }
+    }
+}
+
+impl std::fmt::Display for Register {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Register::W => "w",
+            Register::X => "x",
+            Register::Y => "y",
+            Register::Z => "z",
+        };
+        f.write_str(name)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 enum Value {
     Ident(Register),
@@ -41,14 +124,23 @@ impl FromStr for Value {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        s.parse::<Register>().map(|r| Self::Ident(r)).or_else(|_| {
+        s.parse::<Register>().map(Self::Ident).or_else(|_| {
             s.parse::<i64>()
-                .map(|x| Self::NumberLiteral(x))
-                .map_err(|_| Error::ParseError)
+                .map(Self::NumberLiteral)
+                .map_err(|_| Error::Parse)
         })
     }
 }
 
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Ident(reg) => reg.fmt(f),
+            Value::NumberLiteral(v) => v.fmt(f),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 enum Instruction {
     Inp(Register),
@@ -64,48 +156,65 @@ impl FromStr for Instruction {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut tokens = s.split_ascii_whitespace();
-        let op = match tokens.next() {
-            Some(op) => op,
-            None => unreachable!(),
-        };
+        let mut next = || tokens.next().ok_or(Error::Parse);
+        let op = next()?;
 
-        match op {
+        let instruction = match op {
             "inp" => {
-                let register = tokens.next().unwrap().parse::<Register>()?;
+                let register = next()?.parse::<Register>()?;
                 Ok(Instruction::Inp(register))
             }
             "add" => {
-                let register = tokens.next().unwrap().parse::<Register>()?;
-                let value = tokens.next().unwrap().parse::<Value>()?;
+                let register = next()?.parse::<Register>()?;
+                let value = next()?.parse::<Value>()?;
                 Ok(Instruction::Add(register, value))
             }
             "mul" => {
-                let register = tokens.next().unwrap().parse::<Register>()?;
-                let value = tokens.next().unwrap().parse::<Value>()?;
+                let register = next()?.parse::<Register>()?;
+                let value = next()?.parse::<Value>()?;
                 Ok(Instruction::Mul(register, value))
             }
             "div" => {
-                let register = tokens.next().unwrap().parse::<Register>()?;
-                let value = tokens.next().unwrap().parse::<Value>()?;
+                let register = next()?.parse::<Register>()?;
+                let value = next()?.parse::<Value>()?;
                 Ok(Instruction::Div(register, value))
             }
             "mod" => {
-                let register = tokens.next().unwrap().parse::<Register>()?;
-                let value = tokens.next().unwrap().parse::<Value>()?;
+                let register = next()?.parse::<Register>()?;
+                let value = next()?.parse::<Value>()?;
                 Ok(Instruction::Mod(register, value))
             }
             "eql" => {
-                let register = tokens.next().unwrap().parse::<Register>()?;
-                let value = tokens.next().unwrap().parse::<Value>()?;
+                let register = next()?.parse::<Register>()?;
+                let value = next()?.parse::<Value>()?;
                 Ok(Instruction::Eql(register, value))
             }
 
-            _ => Err(Error::ParseError),
+            _ => Err(Error::Parse),
+        };
+
+        match tokens.next() {
+            Some(_) => Err(Error::Parse),
+            None => instruction,
+        }
+    }
+}
+
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instruction::Inp(reg) => write!(f, "inp {}", reg),
+            Instruction::Add(reg, val) => write!(f, "add {} {}", reg, val),
+            Instruction::Mul(reg, val) => write!(f, "mul {} {}", reg, val),
+            Instruction::Div(reg, val) => write!(f, "div {} {}", reg, val),
+            Instruction::Mod(reg, val) => write!(f, "mod {} {}", reg, val),
+            Instruction::Eql(reg, val) => write!(f, "eql {} {}", reg, val),
         }
     }
 }
 
 #[derive(Debug)]
+#[allow(clippy::upper_case_acronyms)]
 struct ALU {
     w: i64,
     x: i64,
@@ -123,6 +232,10 @@ impl ALU {
         }
     }
 
+    fn registers(&self) -> [i64; 4] {
+        [self.w, self.x, self.y, self.z]
+    }
+
     fn get_register(&self, reg: &Register) -> i64 {
         match reg {
             Register::W => self.w,
@@ -148,33 +261,63 @@ impl ALU {
         }
     }
 
-    fn execute(&mut self, input: &[i64], instructions: &[Instruction]) {
+    fn execute(&mut self, input: &[i64], instructions: &[Instruction]) -> Result<(), Error> {
+        self.run(input, instructions, None)
+    }
+
+    /// Executes the instructions, recording the registers after each one.
+    /// The trace is kept up to the faulting instruction if there is one.
+    fn execute_traced(
+        &mut self,
+        input: &[i64],
+        instructions: &[Instruction],
+    ) -> (Vec<[i64; 4]>, Result<(), Error>) {
+        let mut trace = Vec::with_capacity(instructions.len());
+        let result = self.run(input, instructions, Some(&mut trace));
+        (trace, result)
+    }
+
+    /// Arithmetic wraps around on overflow.
+    fn run(
+        &mut self,
+        input: &[i64],
+        instructions: &[Instruction],
+        mut trace: Option<&mut Vec<[i64; 4]>>,
+    ) -> Result<(), Error> {
         let mut input_iter = input.iter();
 
-        for inst in instructions {
+        for (index, inst) in instructions.iter().enumerate() {
             match inst {
                 Instruction::Inp(reg) => {
-                    let val = input_iter.next().unwrap();
+                    let val = input_iter.next().ok_or(Error::InputExhausted { index })?;
                     self.set_register(reg, *val);
                 }
                 Instruction::Mul(reg, val) => {
                     let mut acc = self.get_register(reg);
-                    acc *= self.get_value(val);
+                    acc = acc.wrapping_mul(self.get_value(val));
                     self.set_register(reg, acc);
                 }
                 Instruction::Add(reg, val) => {
                     let mut acc = self.get_register(reg);
-                    acc += self.get_value(val);
+                    acc = acc.wrapping_add(self.get_value(val));
                     self.set_register(reg, acc);
                 }
                 Instruction::Div(reg, val) => {
                     let mut acc = self.get_register(reg);
-                    acc /= self.get_value(val);
+                    let b = self.get_value(val);
+                    if b == 0 {
+                        return Err(Error::DivideByZero { index });
+                    }
+                    acc = acc.wrapping_div(b);
                     self.set_register(reg, acc);
                 }
                 Instruction::Mod(reg, val) => {
                     let mut acc = self.get_register(reg);
-                    acc = acc % self.get_value(val);
+                    let b = self.get_value(val);
+                    if acc < 0 || b <= 0 {
+                        return Err(Error::InvalidMod { index, a: acc, b });
+                    }
+                    acc %= b;
                     self.set_register(reg, acc);
                 }
                 Instruction::Eql(reg, val) => {
@@ -189,15 +332,47 @@ impl ALU {
                     self.set_register(reg, acc);
                 }
             }
+
+            if let Some(trace) = trace.as_mut() {
+                trace.push(self.registers());
+            }
         }
+
+        Ok(())
     }
 }
 
-fn load_instructions(instr: &[&str]) -> Vec<Instruction> {
+/// Parses the program, skipping blank lines.
+fn load_instructions(instr: &[&str]) -> Result<Vec<Instruction>, Error> {
     instr
         .iter()
-        .map(|x| x.parse::<Instruction>().unwrap())
-        .collect::<Vec<Instruction>>()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            line.parse::<Instruction>()
+                .map_err(|_| Error::InvalidInstruction {
+                    line: i + 1,
+                    text: line.to_string(),
+                })
+        })
+        .collect()
+}
+
+/// Reads an input given as a string of digits, e.g. for `--trace`.
+fn parse_digits(s: &str) -> Result<Vec<i64>, Error> {
+    s.chars()
+        .enumerate()
+        .map(|(i, c)| {
+            c.to_digit(10).map(|d| d as i64).ok_or(Error::InvalidDigit {
+                position: i + 1,
+                found: c,
+            })
+        })
+        .collect()
+}
+
+fn digits_to_string(digits: &[i64]) -> String {
+    digits.iter().map(|d| d.to_string()).collect()
 }
 
 fn sn_to_input(sn: i64) -> Vec<i64> {
@@ -207,29 +382,174 @@ fn sn_to_input(sn: i64) -> Vec<i64> {
         .collect()
 }
 
+/// Times checking model numbers one by one, counting down like a brute force
+/// search, with the interpreter and with the compiled program.
+fn bench(instructions: &[Instruction], count: i64) {
+    let inputs: Vec<Vec<i64>> = (0..count)
+        .map(|i| sn_to_input(99999999999999 - i))
+        .filter(|input| !input.contains(&0))
+        .collect();
+
+    let start = std::time::Instant::now();
+    let interpreted = inputs
+        .iter()
+        .filter(|input| {
+            let mut alu = ALU::new();
+            alu.execute(input, instructions).is_ok() && alu.z == 0
+        })
+        .count();
+    let interpreted_time = start.elapsed();
+
+    let compiled = Compiled::new(instructions);
+    let start = std::time::Instant::now();
+    let compiled_valid = inputs
+        .iter()
+        .filter(|input| compiled.run(input) == Ok(0))
+        .count();
+    let compiled_time = start.elapsed();
+
+    assert_eq!(interpreted, compiled_valid);
+    println!(
+        "{} inputs: interpreter {:?}, compiled {:?} ({:.1}x)",
+        inputs.len(),
+        interpreted_time,
+        compiled_time,
+        interpreted_time.as_secs_f64() / compiled_time.as_secs_f64()
+    );
+}
+
+/// Prints what each block leaves in the registers the rest of the program
+/// reads, as formulas over its input digit and the registers it starts with.
+fn print_symbolic(instructions: &[Instruction]) {
+    let registers = [Register::W, Register::X, Register::Y, Register::Z];
+    let (prelude, blocks) = solver::analyse(instructions);
+
+    let mut evaluator = symbolic::Evaluator::new([0, 0, 0, 0].map(symbolic::constant), 0);
+    evaluator.run(&instructions[prelude]);
+
+    for (i, block) in blocks.iter().enumerate() {
+        // Start from what the previous block can produce, as ranges only.
+        let start = registers.map(|reg| {
+            let range = evaluator.register(&reg).range;
+            match block.live_in[reg.index()] {
+                true => symbolic::var(reg, range),
+                false => symbolic::constant(0),
+            }
+        });
+        evaluator = symbolic::Evaluator::new(start, i);
+        evaluator.run(&instructions[block.range.clone()]);
+
+        let live_out = match blocks.get(i + 1) {
+            Some(next) => next.live_in,
+            None => [false, false, false, true],
+        };
+
+        println!("Block {}:", i);
+        for reg in registers.iter().filter(|reg| live_out[reg.index()]) {
+            let expr = evaluator.register(reg);
+            println!(
+                "  {} = {}    [{}..={}]",
+                reg, expr, expr.range.0, expr.range.1
+            );
+        }
+    }
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let file = File::open("day24/input.txt")?;
+    let mut input_file = "day24/input.txt".to_owned();
+    let mut prune = true;
+    let mut show_blocks = false;
+    let mut trace_input = None;
+    let mut show_symbolic = false;
+    let mut bench_count = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--no-prune" => prune = false,
+            "--blocks" => show_blocks = true,
+            "--symbolic" => show_symbolic = true,
+            "--bench" => bench_count = Some(1_000_000),
+            "--trace" => {
+                trace_input = Some(
+                    args.next()
+                        .ok_or_else(|| Error::InvalidOption(format!("{} requires a value", arg)))?,
+                )
+            }
+            _ => input_file = arg,
+        }
+    }
+
+    let file = File::open(input_file)?;
     let reader = BufReader::new(file);
 
     let lines: std::io::Result<Vec<String>> = reader.lines().collect();
     let lines = lines?;
     let lines: Vec<_> = lines.iter().map(String::as_str).collect();
 
-    let instructions = load_instructions(&lines);
+    let instructions = load_instructions(&lines)?;
+
+    if let Some(trace_input) = trace_input {
+        let input = parse_digits(&trace_input)?;
+
+        let (trace, result) = ALU::new().execute_traced(&input, &instructions);
+        for (i, (inst, [w, x, y, z])) in instructions.iter().zip(trace).enumerate() {
+            println!(
+                "{:4} {:12} w={} x={} y={} z={}",
+                i,
+                inst.to_string(),
+                w,
+                x,
+                y,
+                z
+            );
+        }
+        return Ok(result?);
+    }
 
-    for i in (11111111111111..11211791111365 + 1).rev() {
-        let input = sn_to_input(i);
-        if input.contains(&0) {
-            continue;
+    let mut solver = Solver::new(&instructions, prune);
+
+    if show_blocks {
+        for (i, block) in solver.blocks().iter().enumerate() {
+            let live: Vec<_> = ['w', 'x', 'y', 'z']
+                .iter()
+                .zip(block.live_in)
+                .filter(|(_, live)| *live)
+                .map(|(r, _)| *r)
+                .collect();
+            println!(
+                "Block {}: instructions {:?}, live in {:?}, z divided by {}",
+                i, block.range, live, block.z_divisor
+            );
         }
+    }
 
-        let mut alu = ALU::new();
-        alu.execute(&input, &instructions);
+    if show_symbolic {
+        print_symbolic(&instructions);
+    }
+    if let Some(count) = bench_count {
+        bench(&instructions, count);
+    }
+
+    let largest: Vec<i64> = (1..=9).rev().collect();
+    let smallest: Vec<i64> = (1..=9).collect();
+
+    for (name, digits) in [("Largest", largest), ("Smallest", smallest)] {
+        match solver.find(&digits) {
+            Some(input) => {
+                let mut alu = ALU::new();
+                alu.execute(&input, &instructions)?;
+                if alu.z != 0 {
+                    return Err(Error::Rejected {
+                        input: digits_to_string(&input),
+                        z: alu.z,
+                    }
+                    .into());
+                }
 
-        if alu.z == 0 {
-            println!("Valid: {}", i);
-        } else {
-            //println!("{}", i);
+                println!("{}: {}", name, digits_to_string(&input));
+            }
+            None => println!("{}: no valid model number", name),
         }
     }
 
@@ -270,21 +590,101 @@ fn test_parse_instruction() {
 
 #[test]
 fn simple_program() {
-    let instr = load_instructions(&["inp x", "mul x -1"]);
+    let instr = load_instructions(&["inp x", "mul x -1"]).unwrap();
     let mut alu = ALU::new();
-    alu.execute(&[5], &instr);
+    alu.execute(&[5], &instr).unwrap();
     assert_eq!(alu.x, -5);
 
-    let instr = load_instructions(&["inp z", "inp x", "mul z 3", "eql z x"]);
+    let instr = load_instructions(&["inp z", "inp x", "mul z 3", "eql z x"]).unwrap();
     let mut alu = ALU::new();
-    alu.execute(&[1, 3], &instr);
+    alu.execute(&[1, 3], &instr).unwrap();
     assert_eq!(alu.z, 1);
 
     let instr = load_instructions(&[
         "inp w", "add z w", "mod z 2", "div w 2", "add y w", "mod y 2", "div w 2", "add x w",
         "mod x 2", "div w 2", "mod w 2",
-    ]);
+    ])
+    .unwrap();
     let mut alu = ALU::new();
-    alu.execute(&[5], &instr);
+    alu.execute(&[5], &instr).unwrap();
     assert_eq!([alu.w, alu.x, alu.y, alu.z], [0, 1, 0, 1]);
 }
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!("".parse::<Instruction>(), Err(Error::Parse));
+    assert_eq!("add x".parse::<Instruction>(), Err(Error::Parse));
+    assert_eq!("inp x 1".parse::<Instruction>(), Err(Error::Parse));
+    assert_eq!(
+        "add x 1".parse::<Instruction>().unwrap().to_string(),
+        "add x 1"
+    );
+
+    assert_eq!(
+        load_instructions(&["inp x", "", "  ", "add x 1"])
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(
+        load_instructions(&["inp x", "", "sub x 1"]),
+        Err(Error::InvalidInstruction {
+            line: 3,
+            text: "sub x 1".to_owned()
+        })
+    );
+}
+
+#[test]
+fn test_faults() {
+    let run = |lines: &[&str], input: &[i64]| {
+        ALU::new().execute(input, &load_instructions(lines).unwrap())
+    };
+
+    assert_eq!(
+        run(&["inp x", "inp y"], &[1]),
+        Err(Error::InputExhausted { index: 1 })
+    );
+    assert_eq!(
+        run(&["inp x", "div y x"], &[0]),
+        Err(Error::DivideByZero { index: 1 })
+    );
+    assert_eq!(
+        run(&["inp x", "mod x 2"], &[-3]),
+        Err(Error::InvalidMod {
+            index: 1,
+            a: -3,
+            b: 2
+        })
+    );
+    assert_eq!(
+        run(&["inp x", "mod y x"], &[0]),
+        Err(Error::InvalidMod {
+            index: 1,
+            a: 0,
+            b: 0
+        })
+    );
+}
+
+#[test]
+fn test_trace() {
+    let instr = load_instructions(&["inp x", "add y x", "mul y 3", "div y 0"]).unwrap();
+    let (trace, result) = ALU::new().execute_traced(&[2], &instr);
+
+    assert_eq!(trace, vec![[0, 2, 0, 0], [0, 2, 2, 0], [0, 2, 6, 0]]);
+    assert_eq!(result, Err(Error::DivideByZero { index: 3 }));
+}
+
+#[test]
+fn test_parse_digits() {
+    assert_eq!(parse_digits("1390"), Ok(vec![1, 3, 9, 0]));
+    assert_eq!(
+        parse_digits("13x9"),
+        Err(Error::InvalidDigit {
+            position: 3,
+            found: 'x'
+        })
+    );
+    assert_eq!(parse_digits(""), Ok(vec![]));
+}
@@ -0,0 +1,268 @@
+use std::{collections::HashSet, ops::Range};
+
+use crate::{Compiled, Instruction, Register, Value, ALU};
+
+const REGISTERS: [Register; 4] = [Register::W, Register::X, Register::Y, Register::Z];
+
+/// A part of the program starting at an `inp` instruction and running up to
+/// the next one.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Block {
+    pub range: Range<usize>,
+    /// Registers whose value at the start of the block can affect the rest of
+    /// the program.
+    pub live_in: [bool; 4],
+    /// Product of the literals `z` is divided by in this block.
+    pub z_divisor: i64,
+}
+
+/// Registers read and written by an instruction. Multiplying by zero only
+/// writes its register.
+fn reads_writes(inst: &Instruction) -> (Vec<&Register>, &Register) {
+    match inst {
+        Instruction::Inp(reg) => (vec![], reg),
+        Instruction::Mul(reg, Value::NumberLiteral(0)) => (vec![], reg),
+        Instruction::Add(reg, val)
+        | Instruction::Mul(reg, val)
+        | Instruction::Div(reg, val)
+        | Instruction::Mod(reg, val)
+        | Instruction::Eql(reg, val) => match val {
+            Value::Ident(other) => (vec![reg, other], reg),
+            Value::NumberLiteral(_) => (vec![reg], reg),
+        },
+    }
+}
+
+/// Splits the program into blocks and works out which registers are live at
+/// the start of each one, given that only `z` matters at the end. Anything
+/// before the first `inp` is returned as the prelude.
+pub fn analyse(program: &[Instruction]) -> (Range<usize>, Vec<Block>) {
+    let mut starts: Vec<usize> = program
+        .iter()
+        .enumerate()
+        .filter(|(_, inst)| matches!(inst, Instruction::Inp(_)))
+        .map(|(i, _)| i)
+        .collect();
+    let prelude = 0..starts.first().copied().unwrap_or(program.len());
+    starts.push(program.len());
+
+    let mut live = [false, false, false, true];
+    let mut blocks: Vec<Block> = starts
+        .windows(2)
+        .rev()
+        .map(|w| {
+            let range = w[0]..w[1];
+            for inst in program[range.clone()].iter().rev() {
+                let (reads, writes) = reads_writes(inst);
+                live[writes.index()] = false;
+                for reg in reads {
+                    live[reg.index()] = true;
+                }
+            }
+
+            let z_divisor = program[range.clone()]
+                .iter()
+                .map(|inst| match inst {
+                    Instruction::Div(Register::Z, Value::NumberLiteral(n)) => n.abs().max(1),
+                    _ => 1,
+                })
+                .product();
+
+            Block {
+                range,
+                live_in: live,
+                z_divisor,
+            }
+        })
+        .collect();
+    blocks.reverse();
+
+    (prelude, blocks)
+}
+
+/// Memoised depth-first search for the inputs that leave `z` at zero, one
+/// digit per block.
+pub struct Solver<'a> {
+    program: &'a [Instruction],
+    prelude: Range<usize>,
+    blocks: Vec<Block>,
+    compiled: Vec<Compiled>,
+    /// Largest `z` that the remaining blocks can still bring down to zero,
+    /// when pruning.
+    z_limits: Option<Vec<i64>>,
+    /// Block index and live registers from which no input leads to zero.
+    dead: HashSet<(usize, [i64; 4])>,
+    /// The sorted digits `dead` was found with. Trying the same digits in
+    /// another order keeps it, any other digits start over.
+    dead_digits: Vec<i64>,
+}
+
+impl<'a> Solver<'a> {
+    /// With `prune`, `z` is assumed to only shrink through `div z`, like in
+    /// MONAD, so states where it is larger than the remaining divisors can
+    /// undo are given up on.
+    pub fn new(program: &'a [Instruction], prune: bool) -> Self {
+        let (prelude, blocks) = analyse(program);
+
+        let z_limits = prune.then(|| {
+            let mut limits = vec![1i64; blocks.len() + 1];
+            for (i, block) in blocks.iter().enumerate().rev() {
+                limits[i] = limits[i + 1].saturating_mul(block.z_divisor);
+            }
+            limits
+        });
+
+        let compiled = blocks
+            .iter()
+            .map(|block| Compiled::new(&program[block.range.clone()]))
+            .collect();
+
+        Self {
+            program,
+            prelude,
+            blocks,
+            compiled,
+            z_limits,
+            dead: HashSet::new(),
+            dead_digits: vec![],
+        }
+    }
+
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// Finds the first valid input when trying `digits` in order at every
+    /// position, e.g. `9..=1` for the largest model number.
+    pub fn find(&mut self, digits: &[i64]) -> Option<Vec<i64>> {
+        let mut sorted = digits.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        if sorted != self.dead_digits {
+            self.dead.clear();
+            self.dead_digits = sorted;
+        }
+
+        let mut alu = ALU::new();
+        if alu
+            .execute(&[], &self.program[self.prelude.clone()])
+            .is_err()
+        {
+            return None;
+        }
+
+        let mut input = vec![];
+        match self.search(0, alu.registers(), digits, &mut input) {
+            true => Some(input),
+            false => None,
+        }
+    }
+
+    fn search(
+        &mut self,
+        block: usize,
+        registers: [i64; 4],
+        digits: &[i64],
+        input: &mut Vec<i64>,
+    ) -> bool {
+        let z = registers[Register::Z.index()];
+        if let Some(limits) = &self.z_limits {
+            if z.unsigned_abs() >= limits[block] as u64 {
+                return false;
+            }
+        }
+
+        let Some(current) = self.blocks.get(block) else {
+            return z == 0;
+        };
+
+        let mut key = registers;
+        for reg in REGISTERS.iter() {
+            if !current.live_in[reg.index()] {
+                key[reg.index()] = 0;
+            }
+        }
+        if self.dead.contains(&(block, key)) {
+            return false;
+        }
+
+        for digit in digits {
+            let mut next = registers;
+            // Inputs making the program fault are not valid model numbers.
+            if self.compiled[block].execute(&mut next, &[*digit]).is_err() {
+                continue;
+            }
+
+            input.push(*digit);
+            if self.search(block + 1, next, digits, input) {
+                return true;
+            }
+            input.pop();
+        }
+
+        self.dead.insert((block, key));
+        false
+    }
+}
+
+#[test]
+fn test_analyse() {
+    let program = crate::load_instructions(&[
+        "inp w", "mul x 0", "add x z", "mod x 26", "div z 26", "eql x w", "inp w", "add z w",
+    ])
+    .unwrap();
+    let (prelude, blocks) = analyse(&program);
+
+    assert_eq!(prelude, 0..0);
+    assert_eq!(
+        blocks,
+        vec![
+            Block {
+                range: 0..6,
+                live_in: [false, false, false, true],
+                z_divisor: 26,
+            },
+            Block {
+                range: 6..8,
+                live_in: [false, false, false, true],
+                z_divisor: 1,
+            }
+        ]
+    );
+}
+
+#[test]
+fn test_find() {
+    // z = a - b, so the digits have to be equal.
+    let program = crate::load_instructions(&["inp z", "inp w", "mul w -1", "add z w"]).unwrap();
+    let mut solver = Solver::new(&program, false);
+
+    let largest: Vec<i64> = (1..=9).rev().collect();
+    let smallest: Vec<i64> = (1..=9).collect();
+    assert_eq!(solver.find(&largest), Some(vec![9, 9]));
+    assert_eq!(solver.find(&smallest), Some(vec![1, 1]));
+
+    let program = crate::load_instructions(&["inp z", "add z 10"]).unwrap();
+    assert_eq!(Solver::new(&program, true).find(&smallest), None);
+}
+
+#[test]
+fn test_find_other_digits() {
+    // z = a - b + 1, so the second digit has to be one more than the first.
+    let program =
+        crate::load_instructions(&["inp z", "inp w", "mul w -1", "add z w", "add z 1"]).unwrap();
+    let mut solver = Solver::new(&program, false);
+
+    // With only 1 every state after the first digit is dead, but not with
+    // more digits to choose from.
+    assert_eq!(solver.find(&[1]), None);
+    assert_eq!(solver.find(&[1, 2]), Some(vec![1, 2]));
+    assert_eq!(solver.find(&[2, 1]), Some(vec![1, 2]));
+}
+
+#[test]
+fn test_find_wrapped_z() {
+    // 1 + i64::MAX wraps to i64::MIN, which has no i64 absolute value.
+    let program = crate::load_instructions(&["inp z", "add z 9223372036854775807"]).unwrap();
+    assert_eq!(Solver::new(&program, true).find(&[1]), None);
+}
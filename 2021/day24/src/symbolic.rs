@@ -0,0 +1,451 @@
+use std::rc::Rc;
+
+use crate::{Instruction, Register, Value};
+
+/// Inclusive bounds of the values an expression can take.
+pub type Range = (i64, i64);
+
+pub const DIGIT_RANGE: Range = (1, 9);
+
+/// The range of anything that overflowed. The ALU wraps, so an overflowing
+/// bound says nothing about where the values end up.
+const FULL_RANGE: Range = (i64::MIN, i64::MAX);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Op {
+    Const(i64),
+    /// The n-th input digit.
+    Input(usize),
+    /// A register's value at the start of the evaluated code.
+    Var(Register),
+    Add(Expr, Expr),
+    Mul(Expr, Expr),
+    Div(Expr, Expr),
+    Mod(Expr, Expr),
+    Eql(Expr, Expr),
+    Neq(Expr, Expr),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Node {
+    pub op: Op,
+    pub range: Range,
+}
+
+pub type Expr = Rc<Node>;
+
+fn node(op: Op, range: Range) -> Expr {
+    Rc::new(Node { op, range })
+}
+
+pub fn constant(v: i64) -> Expr {
+    node(Op::Const(v), (v, v))
+}
+
+pub fn input(n: usize) -> Expr {
+    node(Op::Input(n), DIGIT_RANGE)
+}
+
+/// A register with a value in `range`, which is a constant when the range
+/// allows a single value.
+pub fn var(reg: Register, range: Range) -> Expr {
+    match range.0 == range.1 {
+        true => constant(range.0),
+        false => node(Op::Var(reg), range),
+    }
+}
+
+fn as_const(e: &Expr) -> Option<i64> {
+    match e.op {
+        Op::Const(v) => Some(v),
+        _ => None,
+    }
+}
+
+fn bounds(values: [i64; 4]) -> Range {
+    (
+        values.iter().copied().min().unwrap(),
+        values.iter().copied().max().unwrap(),
+    )
+}
+
+/// Bounds of values computed with checked arithmetic, or the full range if
+/// any of them overflowed.
+fn checked_bounds(values: [Option<i64>; 4]) -> Range {
+    match values {
+        [Some(a), Some(b), Some(c), Some(d)] => bounds([a, b, c, d]),
+        _ => FULL_RANGE,
+    }
+}
+
+fn overlaps(a: Range, b: Range) -> bool {
+    a.0 <= b.1 && b.0 <= a.1
+}
+
+fn abs_bound(r: Range) -> i64 {
+    r.0.saturating_abs().max(r.1.saturating_abs())
+}
+
+pub fn add(a: Expr, b: Expr) -> Expr {
+    match (as_const(&a), as_const(&b)) {
+        (Some(x), Some(y)) if x.checked_add(y).is_some() => return constant(x + y),
+        (Some(0), _) => return b,
+        (_, Some(0)) => return a,
+        (Some(_), None) => return add(b, a),
+        _ => {}
+    }
+
+    // a + (e + c) = (a + e) + c, keeping constants to the right
+    if let Op::Add(e, c) = &b.op {
+        if as_const(c).is_some() {
+            return add(add(a, e.clone()), c.clone());
+        }
+    }
+
+    // (e + c1) + c2 = e + (c1 + c2)
+    if let (Op::Add(e, c1), Some(c2)) = (&a.op, as_const(&b)) {
+        if let Some(c) = as_const(c1).and_then(|c1| c1.checked_add(c2)) {
+            return add(e.clone(), constant(c));
+        }
+    }
+
+    let range = match (
+        a.range.0.checked_add(b.range.0),
+        a.range.1.checked_add(b.range.1),
+    ) {
+        (Some(lo), Some(hi)) => (lo, hi),
+        _ => FULL_RANGE,
+    };
+    node(Op::Add(a, b), range)
+}
+
+pub fn mul(a: Expr, b: Expr) -> Expr {
+    match (as_const(&a), as_const(&b)) {
+        (Some(x), Some(y)) if x.checked_mul(y).is_some() => return constant(x * y),
+        (Some(0), _) | (_, Some(0)) => return constant(0),
+        (Some(1), _) => return b,
+        (_, Some(1)) => return a,
+        (Some(_), None) => return mul(b, a),
+        _ => {}
+    }
+
+    let (a0, a1, b0, b1) = (a.range.0, a.range.1, b.range.0, b.range.1);
+    let range = checked_bounds([
+        a0.checked_mul(b0),
+        a0.checked_mul(b1),
+        a1.checked_mul(b0),
+        a1.checked_mul(b1),
+    ]);
+    node(Op::Mul(a, b), range)
+}
+
+/// The quotients of the range bounds, unless the divisor can be zero or one of
+/// them overflows, like `i64::MIN / -1`.
+fn div_corners((a0, a1): Range, (b0, b1): Range) -> Option<[i64; 4]> {
+    if overlaps((b0, b1), (0, 0)) {
+        return None;
+    }
+
+    Some([
+        a0.checked_div(b0)?,
+        a0.checked_div(b1)?,
+        a1.checked_div(b0)?,
+        a1.checked_div(b1)?,
+    ])
+}
+
+pub fn div(a: Expr, b: Expr) -> Expr {
+    match (as_const(&a), as_const(&b)) {
+        (Some(x), Some(y)) if y != 0 && x.checked_div(y).is_some() => return constant(x / y),
+        (_, Some(1)) => return a,
+        _ => {}
+    }
+    if b.range.0 > 0 && abs_bound(a.range) < b.range.0 {
+        return constant(0);
+    }
+
+    let range = match div_corners(a.range, b.range) {
+        Some(corners) => bounds(corners),
+        // `i64::MIN / -1` wraps back to `i64::MIN`.
+        None if a.range.0 == i64::MIN => FULL_RANGE,
+        // Dividing by anything but zero does not grow the magnitude.
+        None => (-abs_bound(a.range), abs_bound(a.range)),
+    };
+    node(Op::Div(a, b), range)
+}
+
+pub fn modulo(a: Expr, b: Expr) -> Expr {
+    match (as_const(&a), as_const(&b)) {
+        (Some(x), Some(y)) if x >= 0 && y > 0 => return constant(x % y),
+        _ => {}
+    }
+    if a.range.0 >= 0 && a.range.1 < b.range.0 {
+        return a;
+    }
+
+    let m = abs_bound(b.range).saturating_sub(1);
+    let range = match a.range.0 >= 0 {
+        true => (0, m.min(a.range.1)),
+        false => (-m, m),
+    };
+    node(Op::Mod(a, b), range)
+}
+
+pub fn eql(a: Expr, b: Expr) -> Expr {
+    if !overlaps(a.range, b.range) {
+        return constant(0);
+    }
+    if a == b {
+        return constant(1);
+    }
+    if as_const(&a).is_some() && as_const(&b).is_none() {
+        return eql(b, a);
+    }
+
+    // Comparing a comparison with 0 or 1 keeps or negates it.
+    match (&a.op, as_const(&b)) {
+        (Op::Eql(x, y), Some(0)) => return neq(x.clone(), y.clone()),
+        (Op::Neq(x, y), Some(0)) => return eql(x.clone(), y.clone()),
+        (Op::Eql(_, _) | Op::Neq(_, _), Some(1)) => return a,
+        _ => {}
+    }
+
+    node(Op::Eql(a, b), (0, 1))
+}
+
+fn neq(a: Expr, b: Expr) -> Expr {
+    node(Op::Neq(a, b), (0, 1))
+}
+
+/// Evaluates instructions over expressions instead of numbers.
+pub struct Evaluator {
+    registers: [Expr; 4],
+    next_input: usize,
+}
+
+impl Evaluator {
+    /// Starts from the given register values, numbering the inputs from
+    /// `first_input`.
+    pub fn new(registers: [Expr; 4], first_input: usize) -> Self {
+        Self {
+            registers,
+            next_input: first_input,
+        }
+    }
+
+    pub fn register(&self, reg: &Register) -> &Expr {
+        &self.registers[reg.index()]
+    }
+
+    fn value(&self, val: &Value) -> Expr {
+        match val {
+            Value::NumberLiteral(v) => constant(*v),
+            Value::Ident(reg) => self.register(reg).clone(),
+        }
+    }
+
+    pub fn run(&mut self, instructions: &[Instruction]) {
+        for inst in instructions {
+            let (reg, result) = match inst {
+                Instruction::Inp(reg) => {
+                    self.next_input += 1;
+                    (reg, input(self.next_input - 1))
+                }
+                Instruction::Add(reg, val) => {
+                    (reg, add(self.register(reg).clone(), self.value(val)))
+                }
+                Instruction::Mul(reg, val) => {
+                    (reg, mul(self.register(reg).clone(), self.value(val)))
+                }
+                Instruction::Div(reg, val) => {
+                    (reg, div(self.register(reg).clone(), self.value(val)))
+                }
+                Instruction::Mod(reg, val) => {
+                    (reg, modulo(self.register(reg).clone(), self.value(val)))
+                }
+                Instruction::Eql(reg, val) => {
+                    (reg, eql(self.register(reg).clone(), self.value(val)))
+                }
+            };
+
+            self.registers[reg.index()] = result;
+        }
+    }
+}
+
+/// Binding strength when printing: comparisons, sums, products, atoms.
+fn precedence(e: &Expr) -> u8 {
+    match e.op {
+        Op::Eql(_, _) | Op::Neq(_, _) => 0,
+        Op::Add(_, _) => 1,
+        Op::Mul(_, _) | Op::Div(_, _) | Op::Mod(_, _) => 2,
+        Op::Const(_) | Op::Input(_) | Op::Var(_) => 3,
+    }
+}
+
+struct Operand<'a>(&'a Expr, u8);
+
+impl std::fmt::Display for Operand<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match precedence(self.0) < self.1 {
+            true => write!(f, "({})", self.0),
+            false => write!(f, "{}", self.0),
+        }
+    }
+}
+
+impl std::fmt::Display for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.op {
+            Op::Const(v) => write!(f, "{}", v),
+            Op::Input(n) => write!(f, "d{}", n),
+            Op::Var(reg) => write!(f, "{}", reg),
+            Op::Add(a, b) => match as_const(b) {
+                Some(c) if c < 0 && c != i64::MIN => write!(f, "{} - {}", Operand(a, 1), -c),
+                _ => write!(f, "{} + {}", Operand(a, 1), Operand(b, 2)),
+            },
+            Op::Mul(a, b) => write!(f, "{} * {}", Operand(a, 2), Operand(b, 3)),
+            Op::Div(a, b) => write!(f, "{} / {}", Operand(a, 2), Operand(b, 3)),
+            Op::Mod(a, b) => write!(f, "{} % {}", Operand(a, 2), Operand(b, 3)),
+            Op::Eql(a, b) => write!(f, "{} == {}", Operand(a, 1), Operand(b, 1)),
+            Op::Neq(a, b) => write!(f, "{} != {}", Operand(a, 1), Operand(b, 1)),
+        }
+    }
+}
+
+#[test]
+fn test_folding() {
+    let d = input(0);
+
+    assert_eq!(add(constant(2), constant(3)), constant(5));
+    assert_eq!(mul(d.clone(), constant(0)), constant(0));
+    assert_eq!(mul(constant(1), d.clone()), d);
+    assert_eq!(add(add(d.clone(), constant(4)), constant(-4)), d);
+    assert_eq!(div(d.clone(), constant(10)), constant(0));
+    assert_eq!(modulo(d.clone(), constant(10)), d);
+
+    // A digit can never equal 10 or more.
+    assert_eq!(eql(add(d.clone(), constant(10)), input(1)), constant(0));
+    assert_eq!(
+        eql(eql(d.clone(), input(1)), constant(0)).to_string(),
+        "d0 != d1"
+    );
+    assert_eq!(mul(add(d, constant(-3)), constant(26)).range, (-52, 156));
+}
+
+#[test]
+fn test_overflowing_division() {
+    // x overflows, so it may be i64::MIN, which wraps when divided by -1.
+    let program = crate::load_instructions(&[
+        "inp x",
+        "mul x -99999999999",
+        "mul x 99999999999",
+        "div x -1",
+        "add z x",
+    ])
+    .unwrap();
+
+    let mut evaluator = Evaluator::new([0, 0, 0, 0].map(constant), 0);
+    evaluator.run(&program);
+
+    assert_eq!(
+        evaluator.register(&Register::Z).to_string(),
+        "d0 * -99999999999 * 99999999999 / -1"
+    );
+    assert_eq!(evaluator.register(&Register::Z).range, FULL_RANGE);
+}
+
+#[test]
+fn test_monad_block() {
+    let program = crate::load_instructions(&[
+        "inp w", "mul x 0", "add x z", "mod x 26", "div z 1", "add x 11", "eql x w", "eql x 0",
+        "mul y 0", "add y 25", "mul y x", "add y 1", "mul z y", "mul y 0", "add y w", "add y 6",
+        "mul y x", "add z y",
+    ])
+    .unwrap();
+
+    let mut registers = [0, 0, 0, 0].map(constant);
+    registers[Register::Z.index()] = var(Register::Z, (0, 1000));
+    let mut evaluator = Evaluator::new(registers, 3);
+    evaluator.run(&program);
+
+    assert_eq!(
+        evaluator.register(&Register::Z).to_string(),
+        "z * 26 + d3 + 6"
+    );
+    assert_eq!(evaluator.register(&Register::Z).range, (7, 26015));
+}
+
+/// Evaluates an expression built from zeroed registers, wrapping like the ALU.
+#[cfg(test)]
+fn eval(e: &Expr, input: &[i64]) -> i64 {
+    match &e.op {
+        Op::Const(v) => *v,
+        Op::Input(n) => input[*n],
+        Op::Var(reg) => panic!("{} has no value", reg),
+        Op::Add(a, b) => eval(a, input).wrapping_add(eval(b, input)),
+        Op::Mul(a, b) => eval(a, input).wrapping_mul(eval(b, input)),
+        Op::Div(a, b) => eval(a, input).wrapping_div(eval(b, input)),
+        Op::Mod(a, b) => eval(a, input) % eval(b, input),
+        Op::Eql(a, b) => (eval(a, input) == eval(b, input)) as i64,
+        Op::Neq(a, b) => (eval(a, input) != eval(b, input)) as i64,
+    }
+}
+
+#[test]
+fn test_matches_interpreter_on_overflow() {
+    use utils::random::XorShift;
+
+    let registers = [Register::W, Register::X, Register::Y, Register::Z];
+    let literals = [-3, -1, 0, 1, 2, 26, 1 << 62, i64::MAX, i64::MIN, -(1 << 40)];
+    let mut rng = XorShift::new(0x853c49e6748fea9b);
+
+    // The program from the review, and random ones full of large literals.
+    let mut programs = vec![crate::load_instructions(&[
+        "inp w",
+        "add z w",
+        "mul z 4611686018427387904",
+        "mul z 4",
+        "eql z 0",
+    ])
+    .unwrap()];
+    programs.extend((0..500).map(|_| {
+        (0..rng.below(20))
+            .map(|_| {
+                let reg = registers[rng.below(4) as usize];
+                let val = match rng.below(2) {
+                    0 => Value::Ident(registers[rng.below(4) as usize]),
+                    _ => Value::NumberLiteral(literals[rng.below(10) as usize]),
+                };
+                match rng.below(6) {
+                    0 => Instruction::Inp(reg),
+                    1 => Instruction::Add(reg, val),
+                    2 => Instruction::Mul(reg, val),
+                    3 => Instruction::Div(reg, val),
+                    4 => Instruction::Mod(reg, val),
+                    _ => Instruction::Eql(reg, val),
+                }
+            })
+            .collect::<Vec<_>>()
+    }));
+
+    for program in programs {
+        let mut evaluator = Evaluator::new([0, 0, 0, 0].map(constant), 0);
+        evaluator.run(&program);
+
+        for _ in 0..20 {
+            let input: Vec<i64> = (0..20).map(|_| rng.below(9) as i64 + 1).collect();
+            let mut alu = crate::ALU::new();
+            if alu.execute(&input, &program).is_err() {
+                continue;
+            }
+
+            for (reg, value) in registers.iter().zip(alu.registers()) {
+                let expr = evaluator.register(reg);
+                let (lo, hi) = expr.range;
+                assert_eq!(eval(expr, &input), value, "{} in {:?}", reg, program);
+                assert!(lo <= value && value <= hi, "{} in {:?}", reg, program);
+            }
+        }
+    }
+}
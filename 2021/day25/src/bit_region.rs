@@ -0,0 +1,203 @@
+use std::fmt::Write;
+
+use utils::{
+    animation::{Frame, ToFrame},
+    simulation::{Scratch, Simulation},
+};
+
+use crate::{Field, Region};
+
+/// Region with each herd stored as one bitset per row, bit `x % 64` of word
+/// `x / 64` standing for column `x`. Whole rows move at once.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitRegion {
+    width: usize,
+    height: usize,
+    words: usize,
+    east: Vec<u64>,
+    south: Vec<u64>,
+    occupied: Scratch<Vec<u64>>,
+    ahead: Scratch<Vec<u64>>,
+    moving: Scratch<Vec<u64>>,
+    south_moving: Scratch<Vec<u64>>,
+}
+
+/// Sets `out[x]` to `row[(x + 1) % width]`.
+fn from_next(row: &[u64], width: usize, out: &mut [u64]) {
+    let last = row.len() - 1;
+    for i in 0..row.len() {
+        let carry = row.get(i + 1).map_or(0, |w| w << 63);
+        out[i] = row[i] >> 1 | carry;
+    }
+    out[last] |= (row[0] & 1) << ((width - 1) % 64);
+}
+
+/// Sets `out[(x + 1) % width]` to `row[x]`.
+fn to_next(row: &[u64], width: usize, out: &mut [u64]) {
+    let last = row.len() - 1;
+    let top = (row[last] >> ((width - 1) % 64)) & 1;
+    for i in 0..row.len() {
+        let carry = match i {
+            0 => top,
+            _ => row[i - 1] >> 63,
+        };
+        out[i] = row[i] << 1 | carry;
+    }
+    out[last] &= last_word_mask(width);
+}
+
+fn last_word_mask(width: usize) -> u64 {
+    match width % 64 {
+        0 => u64::MAX,
+        bits => (1 << bits) - 1,
+    }
+}
+
+impl BitRegion {
+    fn get(&self, x: usize, y: usize) -> Field {
+        let (word, bit) = (y * self.words + x / 64, x % 64);
+        match (self.east[word] >> bit & 1, self.south[word] >> bit & 1) {
+            (1, _) => Field::East,
+            (_, 1) => Field::South,
+            _ => Field::Empty,
+        }
+    }
+
+    /// Moves the east herd and then the south herd, returning how many sea
+    /// cucumbers moved.
+    pub fn next(&mut self) -> usize {
+        let mut moves_counter = 0;
+        let words = self.words;
+
+        for y in 0..self.height {
+            let row = y * words;
+            for i in 0..words {
+                self.occupied[i] = self.east[row + i] | self.south[row + i];
+            }
+            from_next(&self.occupied, self.width, &mut self.ahead);
+
+            for i in 0..words {
+                self.moving[i] = self.east[row + i] & !self.ahead[i];
+                moves_counter += self.moving[i].count_ones() as usize;
+                self.east[row + i] &= !self.moving[i];
+            }
+
+            to_next(&self.moving, self.width, &mut self.ahead);
+            for i in 0..words {
+                self.east[row + i] |= self.ahead[i];
+            }
+        }
+
+        // A south-facing one moves if the field below was free after the east
+        // herd moved, so all of them are found before any moves.
+        for y in 0..self.height {
+            let below = ((y + 1) % self.height) * words;
+            for i in 0..words {
+                let occupied = self.east[below + i] | self.south[below + i];
+                self.south_moving[y * words + i] = self.south[y * words + i] & !occupied;
+            }
+        }
+
+        for y in 0..self.height {
+            let below = ((y + 1) % self.height) * words;
+            for i in 0..words {
+                let m = self.south_moving[y * words + i];
+                moves_counter += m.count_ones() as usize;
+                self.south[y * words + i] &= !m;
+                self.south[below + i] |= m;
+            }
+        }
+
+        moves_counter
+    }
+}
+
+impl Simulation for BitRegion {
+    type Output = usize;
+
+    fn step(&mut self) -> usize {
+        self.next()
+    }
+}
+
+impl From<&Region> for BitRegion {
+    fn from(region: &Region) -> Self {
+        let (height, width) = (region.fields.len(), region.fields[0].len());
+        let words = width.div_ceil(64);
+        let mut east = vec![0; height * words];
+        let mut south = vec![0; height * words];
+
+        for (y, row) in region.fields.iter().enumerate() {
+            for (x, field) in row.iter().enumerate() {
+                let (word, bit) = (y * words + x / 64, 1 << (x % 64));
+                match field {
+                    Field::East => east[word] |= bit,
+                    Field::South => south[word] |= bit,
+                    Field::Empty => {}
+                }
+            }
+        }
+
+        Self {
+            width,
+            height,
+            words,
+            east,
+            south,
+            occupied: Scratch(vec![0; words]),
+            ahead: Scratch(vec![0; words]),
+            moving: Scratch(vec![0; words]),
+            south_moving: Scratch(vec![0; height * words]),
+        }
+    }
+}
+
+impl std::fmt::Display for BitRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                self.get(x, y).fmt(f)?;
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+impl ToFrame for BitRegion {
+    fn to_frame(&self) -> Frame {
+        let mut frame = Frame::new(self.width, self.height, 0);
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let colour = match self.get(x, y) {
+                    Field::Empty => 0,
+                    Field::East => 1,
+                    Field::South => 2,
+                };
+                frame.set(x, y, colour);
+            }
+        }
+
+        frame
+    }
+}
+
+#[test]
+fn test_rotate() {
+    // 70 columns, so rows span two words.
+    let row = [1 | 1 << 63, 1 << 5];
+    let mut out = [0; 2];
+
+    from_next(&row, 70, &mut out);
+    assert_eq!(out, [1 << 62, 1 << 4 | 1 << 5]);
+
+    to_next(&row, 70, &mut out);
+    assert_eq!(out, [1 | 1 << 1, 1]);
+
+    let row = [1 << 63];
+    from_next(&row, 64, &mut out[..1]);
+    assert_eq!(out[0], 1 << 62);
+    to_next(&row, 64, &mut out[..1]);
+    assert_eq!(out[0], 1);
+}
@@ -1,28 +1,83 @@
-use std::{
-    fmt::Write,
-    fs::File,
-    io::{BufRead, BufReader},
+use std::fmt::Write;
+
+use utils::{
+    animation::{self, Colour, Frame, ToFrame},
+    random::XorShift,
+    simulation::Driver,
 };
 
+mod bit_region;
+
+use bit_region::BitRegion;
+
+/// Empty, east-facing and south-facing field colours used when recording an
+/// animation.
+const PALETTE: [Colour; 3] = [[0x00, 0x1e, 0x3c], [0xff, 0x8c, 0x00], [0x3c, 0xdc, 0x78]];
+
 #[derive(Debug)]
 enum Error {
-    InputError(Box<dyn std::error::Error>),
+    Io(std::io::Error),
+    Empty,
+    InvalidField {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    RaggedRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    InvalidOption(String),
 }
 
-#[derive(Clone)]
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {}", e),
+            Self::Empty => f.write_str("empty region"),
+            Self::InvalidField {
+                line,
+                column,
+                found,
+            } => write!(
+                f,
+                "line {}, column {}: invalid field {:?}",
+                line, column, found
+            ),
+            Self::RaggedRow {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {} fields, found {}",
+                line, expected, found
+            ),
+            Self::InvalidOption(s) => write!(f, "invalid option: {}", s),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 enum Field {
     Empty,
     East,
     South,
 }
 
-impl From<char> for Field {
-    fn from(x: char) -> Self {
+/// Gives the character back if it is not a field.
+impl TryFrom<char> for Field {
+    type Error = char;
+
+    fn try_from(x: char) -> Result<Self, Self::Error> {
         match x {
-            '.' => Self::Empty,
-            '>' => Self::East,
-            'v' => Self::South,
-            _ => unreachable!(),
+            '.' => Ok(Self::Empty),
+            '>' => Ok(Self::East),
+            'v' => Ok(Self::South),
+            _ => Err(x),
         }
     }
 }
@@ -104,29 +159,232 @@ impl std::fmt::Display for Region {
     }
 }
 
-fn load_input(path: &str) -> Result<Region, Error> {
-    let fp = File::open(path).map_err(|e| Error::InputError(Box::new(e)))?;
-    let reader = BufReader::new(fp);
+impl ToFrame for Region {
+    fn to_frame(&self) -> Frame {
+        let (y_size, x_size) = (self.fields.len(), self.fields[0].len());
+        let mut frame = Frame::new(x_size, y_size, 0);
 
-    let lines: Result<Vec<String>, _> = reader.lines().collect();
-    let lines = lines.map_err(|e| Error::InputError(Box::new(e)))?;
+        for (y, row) in self.fields.iter().enumerate() {
+            for (x, field) in row.iter().enumerate() {
+                let colour = match field {
+                    Field::Empty => 0,
+                    Field::East => 1,
+                    Field::South => 2,
+                };
+                frame.set(x, y, colour);
+            }
+        }
+
+        frame
+    }
+}
 
-    let mut fields = vec![];
-    for line in lines {
-        let row: Vec<Field> = line.chars().map(|c| c.into()).collect();
+/// Parses one row of fields per line. Blank lines at the end are skipped,
+/// but one between rows is a row of the wrong length.
+fn parse_region(s: &str) -> Result<Region, Error> {
+    let mut fields: Vec<Vec<Field>> = vec![];
+    let lines: Vec<&str> = s.lines().collect();
+    let rows = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(0, |last| last + 1);
+
+    for (i, line) in lines[..rows].iter().enumerate() {
+        let row = line
+            .chars()
+            .enumerate()
+            .map(|(column, c)| {
+                Field::try_from(c).map_err(|found| Error::InvalidField {
+                    line: i + 1,
+                    column: column + 1,
+                    found,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let expected = fields.first().map_or(row.len(), |r| r.len());
+        if row.is_empty() || row.len() != expected {
+            return Err(Error::RaggedRow {
+                line: i + 1,
+                expected,
+                found: row.len(),
+            });
+        }
         fields.push(row);
     }
 
-    Ok(Region { fields })
+    match fields.is_empty() {
+        true => Err(Error::Empty),
+        false => Ok(Region { fields }),
+    }
+}
+
+/// Reads the region from `path`, or from stdin if the path is `-`.
+fn read_region(path: &str) -> Result<Region, Error> {
+    parse_region(&utils::read_input(path).map_err(Error::Io)?)
+}
+
+/// Region filled at random, a third of the fields with each herd.
+fn generate_region(width: usize, height: usize, seed: u64) -> Region {
+    let mut rng = XorShift::new(seed);
+
+    let fields = (0..height)
+        .map(|_| {
+            (0..width)
+                .map(|_| match rng.below(3) {
+                    0 => Field::Empty,
+                    1 => Field::East,
+                    _ => Field::South,
+                })
+                .collect()
+        })
+        .collect();
+
+    Region { fields }
 }
 
-fn main() {
-    let mut region = load_input("day25/input.txt").unwrap();
+/// Runs both simulations on a generated region, checking they agree.
+fn bench(size: usize, steps: usize) {
+    let mut region = generate_region(size, size, 25);
+    let mut bit_region = BitRegion::from(&region);
+
+    let start = std::time::Instant::now();
+    let moves: Vec<usize> = (0..steps).map(|_| region.next()).collect();
+    let grid_time = start.elapsed();
+
+    let start = std::time::Instant::now();
+    let bit_moves: Vec<usize> = (0..steps).map(|_| bit_region.next()).collect();
+    let bit_time = start.elapsed();
 
-    let mut counter = 1;
-    while region.next() > 0 {
-        counter += 1
+    assert_eq!(moves, bit_moves);
+    assert_eq!(region.to_string(), bit_region.to_string());
+    println!(
+        "{}x{}, {} steps: grid {:?}, bitset {:?} ({:.1}x)",
+        size,
+        size,
+        steps,
+        grid_time,
+        bit_time,
+        grid_time.as_secs_f64() / bit_time.as_secs_f64()
+    );
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let record = animation::Options::from_args(&mut args)?;
+    let mut recorder = record.as_ref().map(|r| r.recorder(&PALETTE)).transpose()?;
+
+    let mut input_file = "day25/input.txt".to_owned();
+    let mut at = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bench" => {
+                for size in [139, 500, 2000] {
+                    bench(size, 100);
+                }
+                return Ok(());
+            }
+            "--at" => {
+                let n = args.next().and_then(|n| n.parse::<usize>().ok());
+                at = Some(n.ok_or_else(|| Error::InvalidOption("--at needs a step".to_owned()))?);
+            }
+            _ => input_file = arg,
+        }
+    }
+
+    let region = BitRegion::from(&read_region(&input_file)?);
+    let mut driver = Driver::new(region);
+
+    if let Some(n) = at {
+        println!("{}", driver.state_at(n));
+        return Ok(());
+    }
+
+    let cycle = loop {
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.record(driver.current());
+        }
+
+        driver.step();
+        if let Some(cycle) = driver.cycle() {
+            break cycle;
+        }
+    };
+    println!("{}", driver.current());
+
+    // The herds settle on the first step nothing moves.
+    match cycle.is_steady() {
+        true => println!("{}", cycle.offset + 1),
+        false => println!(
+            "Repeats every {} steps from step {}",
+            cycle.period, cycle.offset
+        ),
     }
-    println!("{}", region);
-    println!("{}", counter);
+
+    if let (Some(record), Some(recorder)) = (record, recorder) {
+        record.write(&recorder, "day25_")?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_bit_region_matches_grid() {
+    let mut region = read_region("input_test.txt").unwrap();
+    let mut bit_region = BitRegion::from(&region);
+
+    let mut steps = 1;
+    loop {
+        let moves = region.next();
+        assert_eq!(bit_region.next(), moves);
+        assert_eq!(bit_region.to_string(), region.to_string());
+
+        if moves == 0 {
+            break;
+        }
+        steps += 1;
+    }
+    assert_eq!(steps, 58);
+
+    // Widths around word boundaries and a single row.
+    for (width, height) in [(1, 5), (63, 7), (64, 3), (65, 9), (130, 1)] {
+        let mut region = generate_region(width, height, width as u64);
+        let mut bit_region = BitRegion::from(&region);
+
+        for _ in 0..20 {
+            assert_eq!(bit_region.next(), region.next());
+            assert_eq!(bit_region.to_string(), region.to_string());
+        }
+    }
+}
+
+#[test]
+fn test_parse_region() {
+    let region = parse_region(".>\nv.\n").unwrap();
+    assert_eq!(region.fields[1], vec![Field::South, Field::Empty]);
+
+    assert!(matches!(
+        parse_region(".>\nvx"),
+        Err(Error::InvalidField {
+            line: 2,
+            column: 2,
+            found: 'x'
+        })
+    ));
+    assert!(matches!(
+        parse_region(".>\nv\n"),
+        Err(Error::RaggedRow {
+            line: 2,
+            expected: 2,
+            found: 1
+        })
+    ));
+    assert_eq!(parse_region(".>\n\n \n").unwrap().fields.len(), 1);
+    assert!(matches!(
+        parse_region(".>\n\nv.\n"),
+        Err(Error::RaggedRow { line: 2, .. })
+    ));
+    assert!(matches!(parse_region(""), Err(Error::Empty)));
+    assert!(matches!(parse_region("\n\n"), Err(Error::Empty)));
 }
@@ -0,0 +1,357 @@
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+pub type Colour = [u8; 3];
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Gif(gif::EncodingError),
+    Png(png::EncodingError),
+    InvalidOption(String),
+    NoFrames,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {}", e),
+            Self::Gif(e) => write!(f, "gif encoding error: {}", e),
+            Self::Png(e) => write!(f, "png encoding error: {}", e),
+            Self::InvalidOption(s) => write!(f, "invalid option: {}", s),
+            Self::NoFrames => f.write_str("no frames recorded"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<gif::EncodingError> for Error {
+    fn from(e: gif::EncodingError) -> Self {
+        Self::Gif(e)
+    }
+}
+
+impl From<png::EncodingError> for Error {
+    fn from(e: png::EncodingError) -> Self {
+        Self::Png(e)
+    }
+}
+
+/// A single frame as palette indices. Pixels outside the frame, e.g. when it
+/// is smaller than the animation, are drawn with the `background` colour.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    background: u8,
+    pixels: Vec<u8>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize, background: u8) -> Self {
+        Self {
+            width,
+            height,
+            background,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, colour: u8) {
+        self.pixels[y * self.width + x] = colour;
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Returns the frame centred on a `width` x `height` canvas and scaled up
+    /// `scale` times.
+    fn render(&self, width: usize, height: usize, scale: usize) -> Vec<u8> {
+        let (offset_x, offset_y) = ((width - self.width) / 2, (height - self.height) / 2);
+        let mut canvas = vec![self.background; width * height * scale * scale];
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let colour = self.get(x, y);
+                for sy in 0..scale {
+                    let row = ((offset_y + y) * scale + sy) * width * scale;
+                    let start = row + (offset_x + x) * scale;
+                    canvas[start..start + scale].fill(colour);
+                }
+            }
+        }
+
+        canvas
+    }
+}
+
+/// Anything that can be drawn as a frame of an animation.
+pub trait ToFrame {
+    fn to_frame(&self) -> Frame;
+}
+
+/// Collects frames of a simulation and writes them as an animated GIF or as a
+/// sequence of numbered PNG files.
+pub struct Recorder {
+    palette: Vec<Colour>,
+    scale: usize,
+    delay: u16,
+    frames: Vec<Frame>,
+}
+
+impl Recorder {
+    pub fn new(palette: Vec<Colour>) -> Self {
+        Self {
+            palette,
+            scale: 1,
+            delay: 10,
+            frames: vec![],
+        }
+    }
+
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Sets the GIF frame delay in hundredths of a second.
+    pub fn delay(mut self, delay: u16) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn record<T: ToFrame>(&mut self, x: &T) {
+        self.frames.push(x.to_frame());
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    fn canvas_size(&self) -> Result<(usize, usize), Error> {
+        let width = self.frames.iter().map(|f| f.width).max();
+        let height = self.frames.iter().map(|f| f.height).max();
+
+        match (width, height) {
+            (Some(width), Some(height)) => Ok((width, height)),
+            _ => Err(Error::NoFrames),
+        }
+    }
+
+    fn flat_palette(&self) -> Vec<u8> {
+        self.palette.iter().flatten().copied().collect()
+    }
+
+    pub fn write_gif<W: Write>(&self, w: W) -> Result<(), Error> {
+        let (width, height) = self.canvas_size()?;
+        let (scaled_width, scaled_height) = (width * self.scale, height * self.scale);
+        let too_large = || {
+            Error::InvalidOption(format!(
+                "{}x{} is too large for a gif",
+                scaled_width, scaled_height
+            ))
+        };
+
+        let mut encoder = gif::Encoder::new(
+            w,
+            u16::try_from(scaled_width).map_err(|_| too_large())?,
+            u16::try_from(scaled_height).map_err(|_| too_large())?,
+            &self.flat_palette(),
+        )?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+
+        for frame in self.frames.iter() {
+            let frame = gif::Frame {
+                width: scaled_width as u16,
+                height: scaled_height as u16,
+                delay: self.delay,
+                buffer: Cow::Owned(frame.render(width, height, self.scale)),
+                ..Default::default()
+            };
+            encoder.write_frame(&frame)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes every frame to `dir` as `<prefix>0000.png`, `<prefix>0001.png`, ...
+    pub fn write_png_sequence(&self, dir: &Path, prefix: &str) -> Result<Vec<PathBuf>, Error> {
+        let (width, height) = self.canvas_size()?;
+        std::fs::create_dir_all(dir)?;
+
+        let mut paths = vec![];
+        for (i, frame) in self.frames.iter().enumerate() {
+            let path = dir.join(format!("{}{:04}.png", prefix, i));
+            let w = BufWriter::new(File::create(&path)?);
+
+            let mut encoder =
+                png::Encoder::new(w, (width * self.scale) as u32, (height * self.scale) as u32);
+            encoder.set_color(png::ColorType::Indexed);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder.set_palette(self.flat_palette());
+
+            let mut writer = encoder.write_header()?;
+            writer.write_image_data(&frame.render(width, height, self.scale))?;
+
+            paths.push(path);
+        }
+
+        Ok(paths)
+    }
+}
+
+pub enum Output {
+    Gif(PathBuf),
+    PngSequence(PathBuf),
+}
+
+/// Recording options taken from the command line:
+/// `--gif <file>` or `--png <dir>`, and optionally `--scale <n>`,
+/// `--delay <centiseconds>` and `--colours <rrggbb,rrggbb,...>`.
+pub struct Options {
+    pub output: Output,
+    pub scale: usize,
+    pub delay: u16,
+    pub palette: Option<Vec<Colour>>,
+}
+
+impl Options {
+    /// Removes the recording options from `args`, leaving the rest for the
+    /// caller. Returns `None` when no output was requested.
+    pub fn from_args(args: &mut Vec<String>) -> Result<Option<Self>, Error> {
+        let mut output = None;
+        let mut scale = 1;
+        let mut delay = 10;
+        let mut palette = None;
+
+        let mut rest = vec![];
+        let mut iter = args.drain(..);
+        while let Some(arg) = iter.next() {
+            let mut value = || {
+                iter.next()
+                    .ok_or_else(|| Error::InvalidOption(format!("{} requires a value", arg)))
+            };
+
+            match arg.as_str() {
+                "--gif" => output = Some(Output::Gif(value()?.into())),
+                "--png" => output = Some(Output::PngSequence(value()?.into())),
+                "--scale" => {
+                    let v = value()?;
+                    scale = v
+                        .parse()
+                        .map_err(|_| Error::InvalidOption(format!("scale {}", v)))?;
+                }
+                "--delay" => {
+                    let v = value()?;
+                    delay = v
+                        .parse()
+                        .map_err(|_| Error::InvalidOption(format!("delay {}", v)))?;
+                }
+                "--colours" => palette = Some(parse_palette(&value()?)?),
+                _ => rest.push(arg),
+            }
+        }
+        drop(iter);
+        *args = rest;
+
+        Ok(output.map(|output| Self {
+            output,
+            scale,
+            delay,
+            palette,
+        }))
+    }
+
+    /// Builds a recorder, using `default_palette` unless colours were given.
+    /// A custom palette must have at least as many colours as the default one.
+    pub fn recorder(&self, default_palette: &[Colour]) -> Result<Recorder, Error> {
+        let palette = match &self.palette {
+            Some(p) if p.len() < default_palette.len() => {
+                return Err(Error::InvalidOption(format!(
+                    "expected {} colours, got {}",
+                    default_palette.len(),
+                    p.len()
+                )))
+            }
+            Some(p) => p.clone(),
+            None => default_palette.to_vec(),
+        };
+
+        Ok(Recorder::new(palette).scale(self.scale).delay(self.delay))
+    }
+
+    pub fn write(&self, recorder: &Recorder, prefix: &str) -> Result<(), Error> {
+        match &self.output {
+            Output::Gif(path) => recorder.write_gif(BufWriter::new(File::create(path)?)),
+            Output::PngSequence(dir) => recorder.write_png_sequence(dir, prefix).map(|_| ()),
+        }
+    }
+}
+
+/// Parses a comma separated list of `rrggbb` hex colours.
+pub fn parse_palette(s: &str) -> Result<Vec<Colour>, Error> {
+    s.split(',')
+        .map(|c| {
+            let c = c.trim().trim_start_matches('#');
+            let channel = |i: usize| {
+                c.get(i..i + 2)
+                    .and_then(|h| u8::from_str_radix(h, 16).ok())
+                    .ok_or_else(|| Error::InvalidOption(format!("colour {}", c)))
+            };
+
+            match c.len() {
+                6 => Ok([channel(0)?, channel(2)?, channel(4)?]),
+                _ => Err(Error::InvalidOption(format!("colour {}", c))),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_parse_palette() {
+    assert_eq!(
+        parse_palette("000000,#ff8000").unwrap(),
+        vec![[0, 0, 0], [255, 128, 0]]
+    );
+    assert!(parse_palette("fff").is_err());
+    assert!(parse_palette("gg0000").is_err());
+}
+
+#[test]
+fn test_render_centres_and_scales() {
+    let mut frame = Frame::new(1, 1, 0);
+    frame.set(0, 0, 1);
+
+    assert_eq!(
+        frame.render(3, 1, 2),
+        vec![0, 0, 1, 1, 0, 0, 0, 0, 1, 1, 0, 0]
+    );
+}
+
+#[test]
+fn test_options_from_args() {
+    let mut args: Vec<String> = ["input.txt", "--gif", "out.gif", "--scale", "4"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+    let options = Options::from_args(&mut args).unwrap().unwrap();
+    assert_eq!(args, vec!["input.txt".to_string()]);
+    assert_eq!(options.scale, 4);
+    assert!(matches!(options.output, Output::Gif(_)));
+
+    let mut args = vec!["input.txt".to_string()];
+    assert!(Options::from_args(&mut args).unwrap().is_none());
+}
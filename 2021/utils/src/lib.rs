@@ -0,0 +1,12 @@
+pub mod animation;
+pub mod ocr;
+pub mod random;
+pub mod simulation;
+
+/// Reads the whole input from `path`, or from stdin if the path is `-`.
+pub fn read_input(path: &str) -> std::io::Result<String> {
+    match path {
+        "-" => std::io::read_to_string(std::io::stdin()),
+        path => std::fs::read_to_string(path),
+    }
+}
@@ -0,0 +1,154 @@
+/// Width and height of a letter in the font Advent of Code draws answers in.
+/// Letters are one blank column apart.
+pub const GLYPH_WIDTH: usize = 4;
+pub const GLYPH_HEIGHT: usize = 6;
+
+const PITCH: usize = GLYPH_WIDTH + 1;
+
+#[rustfmt::skip]
+const FONT: [(char, [&str; GLYPH_HEIGHT]); 17] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    Empty,
+    /// The lit pixels span this many rows instead of `GLYPH_HEIGHT`.
+    WrongHeight(usize),
+    /// The `index`-th letter, drawn with `#` and `.` including the blank
+    /// column after it, is not in the font.
+    Unrecognised {
+        index: usize,
+        glyph: String,
+    },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => f.write_str("no lit pixels to read"),
+            Self::WrongHeight(h) => write!(
+                f,
+                "letters are {} pixels high, found {} rows",
+                GLYPH_HEIGHT, h
+            ),
+            Self::Unrecognised { index, glyph } => {
+                write!(f, "letter {} is not recognised:\n{}", index + 1, glyph)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Reads the letters drawn by `points`, given as `(x, y)` with the top left
+/// letter starting at `(0, 0)`.
+pub fn read_points(points: impl IntoIterator<Item = (usize, usize)>) -> Result<String, Error> {
+    let points: Vec<(usize, usize)> = points.into_iter().collect();
+    let width = points
+        .iter()
+        .map(|(x, _)| x + 1)
+        .max()
+        .ok_or(Error::Empty)?;
+    let height = points
+        .iter()
+        .map(|(_, y)| y + 1)
+        .max()
+        .ok_or(Error::Empty)?;
+
+    let mut rows = vec![vec![false; width]; height];
+    for (x, y) in points {
+        rows[y][x] = true;
+    }
+
+    read_rows(&rows)
+}
+
+/// Reads the letters drawn by the lit pixels of `rows`.
+pub fn read_rows(rows: &[Vec<bool>]) -> Result<String, Error> {
+    if rows.len() != GLYPH_HEIGHT {
+        return Err(Error::WrongHeight(rows.len()));
+    }
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    if width == 0 {
+        return Err(Error::Empty);
+    }
+
+    let lit = |x: usize, y: usize| rows[y].get(x).copied().unwrap_or(false);
+    (0..width.div_ceil(PITCH))
+        .map(|index| {
+            let glyph: Vec<String> = (0..GLYPH_HEIGHT)
+                .map(|y| {
+                    (index * PITCH..(index + 1) * PITCH)
+                        .map(|x| if lit(x, y) { '#' } else { '.' })
+                        .collect()
+                })
+                .collect();
+
+            let spaced = glyph.iter().all(|row| row.ends_with('.'));
+            FONT.iter()
+                .find(|(_, rows)| spaced && rows.iter().zip(&glyph).all(|(r, g)| g.starts_with(r)))
+                .map(|(c, _)| *c)
+                .ok_or_else(|| Error::Unrecognised {
+                    index,
+                    glyph: glyph.join("\n"),
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+fn draw(text: &str) -> Vec<(usize, usize)> {
+    let mut points = vec![];
+    for (i, c) in text.chars().enumerate() {
+        let (_, rows) = FONT.iter().find(|(f, _)| *f == c).unwrap();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, pixel) in row.chars().enumerate() {
+                if pixel == '#' {
+                    points.push((i * PITCH + x, y));
+                }
+            }
+        }
+    }
+    points
+}
+
+#[test]
+fn test_read() {
+    let alphabet: String = FONT.iter().map(|(c, _)| *c).collect();
+    assert_eq!(read_points(draw(&alphabet)), Ok(alphabet));
+    assert_eq!(read_points(draw("IL")), Ok("IL".to_owned()));
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(read_points([]), Err(Error::Empty));
+    assert_eq!(read_points([(0, 0), (0, 7)]), Err(Error::WrongHeight(8)));
+
+    // An H with its bar one pixel too long.
+    let mut points = draw("AH");
+    points.push((9, 2));
+    assert_eq!(
+        read_points(points),
+        Err(Error::Unrecognised {
+            index: 1,
+            glyph: "#..#.\n#..#.\n#####\n#..#.\n#..#.\n#..#.".to_owned()
+        })
+    );
+}
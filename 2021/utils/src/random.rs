@@ -0,0 +1,33 @@
+/// Xorshift generator, for generated inputs and tests that have to come out
+/// the same on every run. Not meant for anything else.
+#[derive(Debug, Clone)]
+pub struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    /// A zero seed would only ever give zeros, so it is taken as 1.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed.max(1) }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// A number in `0..bound`.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+#[test]
+fn test_sequence() {
+    let mut rng = XorShift::new(0);
+    assert_eq!(rng.next_u64(), 1082269761);
+    assert_eq!(XorShift::new(1).next_u64(), 1082269761);
+    assert!((0..1000).all(|_| rng.below(7) < 7));
+}
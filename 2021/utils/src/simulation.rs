@@ -0,0 +1,232 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    ops::{Deref, DerefMut},
+};
+
+/// Anything that advances in discrete steps.
+pub trait Simulation {
+    /// What a step reports, e.g. how many things moved.
+    type Output;
+
+    fn step(&mut self) -> Self::Output;
+}
+
+/// The state after `offset + period` steps is the same as after `offset`
+/// steps, and it repeats from there on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub offset: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// Whether the simulation stopped changing.
+    pub fn is_steady(&self) -> bool {
+        self.period == 1
+    }
+
+    /// The first step with the same state as step `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        match n < self.offset {
+            true => n,
+            false => self.offset + (n - self.offset) % self.period,
+        }
+    }
+}
+
+fn hash_of<T: Hash>(x: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    x.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Runs a simulation while remembering every state it passes through, so that
+/// a repeated state is noticed as soon as it comes up. Once the cycle is known
+/// any later step can be looked up without simulating it.
+pub struct Driver<S> {
+    /// The state after each step, starting with the initial one.
+    states: Vec<S>,
+    seen: HashMap<u64, Vec<usize>>,
+    cycle: Option<Cycle>,
+}
+
+impl<S> Driver<S>
+where
+    S: Simulation + Clone + Hash + Eq,
+{
+    pub fn new(start: S) -> Self {
+        let mut seen = HashMap::new();
+        seen.insert(hash_of(&start), vec![0]);
+
+        Self {
+            states: vec![start],
+            seen,
+            cycle: None,
+        }
+    }
+
+    /// Number of steps simulated so far.
+    pub fn steps(&self) -> usize {
+        self.states.len() - 1
+    }
+
+    pub fn current(&self) -> &S {
+        &self.states[self.steps()]
+    }
+
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// Simulates one more step and returns its output, or `None` when a cycle
+    /// was already found and there is nothing new to simulate.
+    pub fn step(&mut self) -> Option<S::Output> {
+        if self.cycle.is_some() {
+            return None;
+        }
+
+        let mut next = self.current().clone();
+        let output = next.step();
+        let step = self.states.len();
+
+        let same_hash = self.seen.entry(hash_of(&next)).or_default();
+        if let Some(first) = same_hash.iter().find(|i| self.states[**i] == next) {
+            self.cycle = Some(Cycle {
+                offset: *first,
+                period: step - first,
+            });
+        }
+        same_hash.push(step);
+        self.states.push(next);
+
+        Some(output)
+    }
+
+    /// Simulates until a state repeats, giving up after `max_steps` steps.
+    pub fn find_cycle(&mut self, max_steps: usize) -> Option<Cycle> {
+        while self.cycle.is_none() && self.steps() < max_steps {
+            self.step();
+        }
+        self.cycle
+    }
+
+    /// The state after `n` steps, skipping the repetitions once a cycle is
+    /// found on the way.
+    pub fn state_at(&mut self, n: usize) -> &S {
+        while self.cycle.is_none() && self.steps() < n {
+            self.step();
+        }
+
+        match self.cycle {
+            Some(cycle) => &self.states[cycle.reduce(n)],
+            None => &self.states[n],
+        }
+    }
+}
+
+/// Scratch space a simulation reuses between steps. It is not part of the
+/// state, so it always compares equal and hashes to nothing, and the state
+/// can derive `PartialEq` and `Hash` with it as a field.
+#[derive(Debug, Clone, Default)]
+pub struct Scratch<T>(pub T);
+
+impl<T> Deref for Scratch<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Scratch<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> PartialEq for Scratch<T> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<T> Eq for Scratch<T> {}
+
+impl<T> Hash for Scratch<T> {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+#[cfg(test)]
+#[derive(Clone, Hash, PartialEq, Eq)]
+struct Counter(u32);
+
+#[cfg(test)]
+impl Simulation for Counter {
+    type Output = u32;
+
+    fn step(&mut self) -> u32 {
+        self.0 = match self.0 > 6 {
+            true => self.0 - 4,
+            false => (self.0 + 2) % 6,
+        };
+        self.0
+    }
+}
+
+#[test]
+fn test_find_cycle() {
+    // 12, 8, 4, 0, 2, 4, ...
+    let mut driver = Driver::new(Counter(12));
+
+    assert_eq!(driver.find_cycle(3), None);
+    assert_eq!(
+        driver.find_cycle(100),
+        Some(Cycle {
+            offset: 2,
+            period: 3
+        })
+    );
+    assert_eq!(driver.steps(), 5);
+    assert_eq!(driver.step(), None);
+
+    assert_eq!(driver.state_at(1).0, 8);
+    assert_eq!(driver.state_at(1000).0, 2);
+    assert_eq!(driver.state_at(1_000_000_001).0, 4);
+    assert!(!driver.cycle().unwrap().is_steady());
+}
+
+#[cfg(test)]
+#[derive(Clone, Hash, PartialEq, Eq)]
+struct Halving(u32);
+
+#[cfg(test)]
+impl Simulation for Halving {
+    type Output = ();
+
+    fn step(&mut self) {
+        self.0 /= 2;
+    }
+}
+
+#[test]
+fn test_steady_state() {
+    // 20, 10, 5, 2, 1, 0, 0
+    let mut driver = Driver::new(Halving(20));
+    let cycle = driver.find_cycle(100).unwrap();
+
+    assert!(cycle.is_steady());
+    assert_eq!(cycle.offset, 5);
+    assert_eq!(driver.state_at(usize::MAX).0, 0);
+}
+
+#[test]
+fn test_scratch_is_ignored() {
+    #[derive(Hash, PartialEq, Eq)]
+    struct State(u32, Scratch<Vec<u32>>);
+
+    let (a, b) = (State(1, Scratch(vec![1])), State(1, Scratch(vec![2])));
+    assert!(a == b);
+    assert_eq!(hash_of(&a), hash_of(&b));
+    assert!(a != State(2, Scratch(vec![1])));
+}